@@ -1,14 +1,24 @@
 use itertools::Itertools;
 
-use crate::item::{Item, ItemType};
+use crate::{
+    enchantments::Enchantment,
+    item::{Item, ItemType, OVER_MAX_LEVEL},
+};
 
-#[derive(PartialEq)]
-enum AnvilBehavior {
+/// the most levels a single anvil use can cost in survival. anything more is "Too Expensive!"
+pub const MAX_COST: u32 = 39;
+
+/// the edition whose anvil rules are followed
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum AnvilBehavior {
     Java,
     Bedrock,
 }
 
-#[derive(PartialEq)]
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct AnvilCombinationResults {
     pub lowest_cost: u32,
     pub lowest_solution: Vec<Item>,
@@ -17,7 +27,176 @@ pub struct AnvilCombinationResults {
     pub rank: AnvilCombinationRank,
 }
 
+/// a single use of the anvil within a combination plan
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AnvilStep {
+    pub target: Item,
+    pub sacrifice: Item,
+    pub cost: u32,
+    pub result: Item,
+    /// the enchantment levels of the inputs that didn't make it into the result
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub losses: Vec<LevelLoss>,
+}
+
+/// enchantment levels that are thrown away by an anvil use
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum LevelLoss {
+    /// the enchantment conflicts with one on the target, so it was dropped
+    Conflicting {
+        enchantment: Enchantment,
+        level: u32,
+    },
+    /// the enchantment can't go on the target, so it was dropped
+    Incompatible {
+        enchantment: Enchantment,
+        level: u32,
+    },
+    /// the two levels weren't equal, so the lower one was thrown away instead of merging
+    Unmerged {
+        enchantment: Enchantment,
+        kept: u32,
+        wasted: u32,
+    },
+}
+
+/// a plan drawn as a tree of merges, with the source items as leaves
+#[derive(Clone, Debug, PartialEq)]
+pub enum AnvilTree {
+    Leaf(Item),
+    Merge {
+        target: Box<AnvilTree>,
+        sacrifice: Box<AnvilTree>,
+        cost: u32,
+        result: Item,
+    },
+}
+
+impl AnvilTree {
+    /// the item at this point of the tree
+    pub fn item(&self) -> &Item {
+        match self {
+            Self::Leaf(item) => item,
+            Self::Merge { result, .. } => result,
+        }
+    }
+}
+
+impl AnvilStep {
+    /// whether the anvil refuses this step in survival
+    pub fn is_too_expensive(&self) -> bool {
+        self.cost > MAX_COST
+    }
+}
+
+/// what happened to one enchantment of the sacrifice
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum EnchantmentOutcome {
+    /// the target didn't have it, so it was added
+    Added,
+    /// the target already had it, so the levels were merged.
+    /// if the levels weren't equal, the lower one is wasted.
+    Merged { wasted: Option<u32> },
+    /// it conflicts with an enchantment on the target, so it was dropped
+    Conflicting,
+    /// it can't go on the target, so it was dropped
+    Incompatible,
+}
+
+/// the part of an anvil use's price that comes from one enchantment of the sacrifice
+#[derive(Clone, Debug, PartialEq)]
+pub struct EnchantmentCost {
+    pub enchantment: Enchantment,
+    pub outcome: EnchantmentOutcome,
+    /// the level the enchantment ends up at, or the level that was dropped
+    pub level: u32,
+    pub multiplier: u32,
+    pub from_book: bool,
+    pub cost: u32,
+}
+
+/// where every level of an anvil use's price comes from
+#[derive(Clone, Debug, PartialEq)]
+pub struct CostBreakdown {
+    pub target_penalty: u32,
+    pub sacrifice_penalty: u32,
+    pub enchantments: Vec<EnchantmentCost>,
+}
+
+impl CostBreakdown {
+    /// the enchantment levels that didn't make it into the result
+    pub fn losses(&self) -> Vec<LevelLoss> {
+        self.enchantments
+            .iter()
+            .filter_map(|cost| {
+                let (enchantment, level) = (cost.enchantment, cost.level);
+
+                match cost.outcome {
+                    EnchantmentOutcome::Added | EnchantmentOutcome::Merged { wasted: None } => None,
+                    EnchantmentOutcome::Merged {
+                        wasted: Some(wasted),
+                    } => Some(LevelLoss::Unmerged {
+                        enchantment,
+                        kept: level,
+                        wasted,
+                    }),
+                    EnchantmentOutcome::Conflicting => {
+                        Some(LevelLoss::Conflicting { enchantment, level })
+                    }
+                    EnchantmentOutcome::Incompatible => {
+                        Some(LevelLoss::Incompatible { enchantment, level })
+                    }
+                }
+            })
+            .collect()
+    }
+
+    pub fn total(&self) -> u32 {
+        self.target_penalty
+            + self.sacrifice_penalty
+            + self
+                .enchantments
+                .iter()
+                .map(|enchantment| enchantment.cost)
+                .sum::<u32>()
+    }
+}
+
+/// books that can be left out of the inventory without changing the final item
+#[derive(Debug, PartialEq)]
+pub struct RedundantBooks {
+    /// the indices of the books to leave out
+    pub books: Vec<usize>,
+    /// how many levels the best plan saves without them
+    pub levels_saved: u32,
+}
+
+/// a plan the player put together by hand, priced step by step
+#[derive(Debug, PartialEq)]
+pub struct ManualPlan {
+    /// every step so far, and whether it loses enchantment levels
+    pub steps: Vec<(AnvilStep, AnvilCombinationRank)>,
+    /// the items that haven't been used as a sacrifice yet, with results in place of their targets
+    pub remaining: Vec<Item>,
+}
+
+impl ManualPlan {
+    pub fn total_cost(&self) -> u32 {
+        self.steps.iter().map(|(step, _)| step.cost).sum()
+    }
+
+    /// whether every item has been combined into one
+    pub fn is_finished(&self) -> bool {
+        self.remaining.len() <= 1
+    }
+}
+
 #[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
 pub enum AnvilCombinationRank {
     Perfect,
     Flawed,
@@ -25,21 +204,58 @@ pub enum AnvilCombinationRank {
 
 pub struct Anvil {
     behavior: AnvilBehavior,
+    /// whether levels above the maximum are kept, for items made with commands
+    over_max: bool,
 }
 
 impl Anvil {
-    pub fn new_java() -> Self {
+    pub fn new(behavior: AnvilBehavior) -> Self {
         Anvil {
-            behavior: AnvilBehavior::Java,
+            behavior,
+            over_max: false,
         }
     }
 
+    pub fn new_java() -> Self {
+        Anvil::new(AnvilBehavior::Java)
+    }
+
+    #[cfg(test)]
     pub fn new_bedrock() -> Self {
-        Anvil {
-            behavior: AnvilBehavior::Bedrock,
+        Anvil::new(AnvilBehavior::Bedrock)
+    }
+
+    /// lets levels go past the maximum of each enchantment, up to `OVER_MAX_LEVEL`.
+    /// this only affects the levels items can hold. like in the game, merging still
+    /// caps every enchantment from the sacrifice at its maximum.
+    pub fn with_over_max(mut self, over_max: bool) -> Self {
+        self.over_max = over_max;
+        self
+    }
+
+    pub fn behavior(&self) -> AnvilBehavior {
+        self.behavior
+    }
+
+    pub fn allows_over_max(&self) -> bool {
+        self.over_max
+    }
+
+    /// the highest level an item can hold in this anvil
+    pub fn max_level(&self, enchantment: Enchantment) -> u32 {
+        if self.over_max {
+            OVER_MAX_LEVEL
+        } else {
+            enchantment.max_level()
         }
     }
 
+    /// the highest level merging can raise the enchantment to.
+    /// this is the enchantment's maximum even in over-max mode, since the game caps merges there.
+    pub fn max_merged_level(&self, enchantment: Enchantment) -> u32 {
+        enchantment.max_level()
+    }
+
     /// combines the target and sacrifice items in this anvil.
     /// returns `None` if the items are incompatible.
     /// returns a tuple containing the price, resulting item, and whether enchantment levels are lost.
@@ -48,20 +264,41 @@ impl Anvil {
         target: Item,
         sacrifice: Item,
     ) -> Option<(u32, Item, AnvilCombinationRank)> {
+        self.combine_explained(target, sacrifice)
+            .map(|(cost, item, rank, _)| (cost, item, rank))
+    }
+
+    /// like `combine`, but also returns where every level of the price comes from
+    pub fn combine_explained(
+        &self,
+        target: Item,
+        sacrifice: Item,
+    ) -> Option<(u32, Item, AnvilCombinationRank, CostBreakdown)> {
         let sacrifice_is_book = sacrifice.item_type() == &ItemType::EnchantedBook;
 
-        // if the two item types are incompatible, return None
-        if target.item_type() != sacrifice.item_type() && !sacrifice_is_book {
+        // if the two items are incompatible, return None
+        if !target.is_same_kind(&sacrifice) && !sacrifice_is_book {
             return None;
         }
 
         let mut new_item = target.clone();
         let mut rank = AnvilCombinationRank::Perfect;
+        // the result carries on from whichever item has been through the anvil more
+        let anvil_uses = target.anvil_uses().max(sacrifice.anvil_uses()) + 1;
 
-        let mut total_cost = target.work_penalty() + sacrifice.work_penalty();
+        let mut breakdown = CostBreakdown {
+            target_penalty: target.work_penalty(),
+            sacrifice_penalty: sacrifice.work_penalty(),
+            enchantments: Vec::new(),
+        };
 
         for (enchantment, sacrifice_level) in sacrifice.into_enchantments() {
-            let cost = match new_item.level_of(enchantment.clone()) {
+            let multiplier = match self.behavior {
+                AnvilBehavior::Java => enchantment.java_multiplier(sacrifice_is_book),
+                AnvilBehavior::Bedrock => enchantment.bedrock_multiplier(sacrifice_is_book),
+            };
+
+            let (outcome, level, cost) = match new_item.level_of(enchantment) {
                 // the enchantment already exists on the target item
                 Some(target_level) => {
                     // if they have the same level, increment the level.
@@ -71,55 +308,210 @@ impl Anvil {
                     } else {
                         u32::max(target_level, sacrifice_level)
                     })
-                    .min(enchantment.max_level());
+                    .min(self.max_merged_level(enchantment));
 
-                    new_item.enchant(enchantment, new_level);
+                    new_item.enchant_over_max(enchantment, new_level);
 
-                    // the combination is flawed if an enchantment is "lost"
-                    if sacrifice_level != target_level {
+                    // the combination is flawed if an enchantment is "lost",
+                    // including levels above the maximum that get capped
+                    if sacrifice_level != target_level || new_level < target_level {
                         rank = AnvilCombinationRank::Flawed;
                     }
 
                     // in java, the enchantment cost is the final level.
                     // in bedrock, it's the difference between the final and initial levels.
-                    match self.behavior {
+                    let cost = match self.behavior {
                         AnvilBehavior::Java => new_level,
-                        AnvilBehavior::Bedrock => new_level - target_level,
-                    }
+                        AnvilBehavior::Bedrock => new_level.saturating_sub(target_level),
+                    };
+
+                    let wasted = (sacrifice_level != target_level)
+                        .then(|| u32::min(target_level, sacrifice_level));
+
+                    (
+                        EnchantmentOutcome::Merged { wasted },
+                        new_level,
+                        cost * multiplier,
+                    )
                 }
                 // if the enchantment doesn't exist on the target, add it on.
                 None => {
-                    if !new_item.has_conflict(&enchantment) {
+                    if new_item.has_conflict(&enchantment, self.behavior) {
                         // if the enchantments are conflicting, this costs one level in java
-                        if self.behavior == AnvilBehavior::Java {
-                            total_cost += 1;
-                        }
+                        let cost = match self.behavior {
+                            AnvilBehavior::Java => 1,
+                            AnvilBehavior::Bedrock => 0,
+                        };
 
-                        0
-                    } else if !new_item.is_compatible(&enchantment) {
-                        0
+                        (EnchantmentOutcome::Conflicting, sacrifice_level, cost)
+                    } else if !new_item.is_compatible(&enchantment, self.behavior) {
+                        (EnchantmentOutcome::Incompatible, sacrifice_level, 0)
                     } else {
-                        new_item.enchant(enchantment, sacrifice_level);
+                        let level = sacrifice_level.min(self.max_merged_level(enchantment));
+                        new_item.enchant_over_max(enchantment, level);
 
-                        sacrifice_level
+                        (EnchantmentOutcome::Added, level, level * multiplier)
                     }
                 }
             };
 
-            total_cost += cost
-                * match self.behavior {
-                    AnvilBehavior::Java => enchantment.java_multiplier(sacrifice_is_book),
-                    AnvilBehavior::Bedrock => enchantment.bedrock_multiplier(sacrifice_is_book),
-                };
+            breakdown.enchantments.push(EnchantmentCost {
+                enchantment,
+                outcome,
+                level,
+                multiplier,
+                from_book: sacrifice_is_book,
+                cost,
+            });
+        }
+
+        new_item.set_anvil_uses(anvil_uses);
+        Some((breakdown.total(), new_item, rank, breakdown))
+    }
+
+    /// splits a solution from `combine_many` into the individual anvil uses, in the order they happen.
+    /// items are combined in pairs, and the results are combined again until a single item is left.
+    pub fn steps(&self, solution: Vec<Item>) -> Vec<AnvilStep> {
+        self.checked_steps(solution)
+            .expect("solutions from combine_many can be combined")
+    }
+
+    /// like `steps`, but returns `None` if two of the items can't be combined
+    fn checked_steps(&self, solution: Vec<Item>) -> Option<Vec<AnvilStep>> {
+        let mut items = solution;
+        let mut steps = Vec::new();
+
+        while items.len() > 1 {
+            let mut new_items = Vec::new();
+
+            for _ in 0..items.len() / 2 {
+                let target = items.remove(0);
+                let sacrifice = items.remove(0);
+
+                let (cost, result, _, breakdown) =
+                    self.combine_explained(target.clone(), sacrifice.clone())?;
+                new_items.push(result.clone());
+
+                steps.push(AnvilStep {
+                    target,
+                    sacrifice,
+                    cost,
+                    result,
+                    losses: breakdown.losses(),
+                });
+            }
+
+            items = [new_items, items].concat();
         }
 
-        new_item.increment_anvil_uses();
-        Some((total_cost, new_item, rank))
+        Some(steps)
+    }
+
+    /// builds the tree of merges for a solution from `combine_many`, out of its `steps`
+    pub fn tree(&self, solution: Vec<Item>) -> Option<AnvilTree> {
+        let steps = self.checked_steps(solution.clone())?;
+        let mut nodes: Vec<AnvilTree> = solution.into_iter().map(AnvilTree::Leaf).collect();
+
+        // like `items_after`, each merge takes the place of its target
+        for step in steps {
+            let mut target = nodes.iter().position(|node| node.item() == &step.target)?;
+            let target_node = nodes.remove(target);
+
+            let sacrifice = nodes
+                .iter()
+                .position(|node| node.item() == &step.sacrifice)?;
+            let sacrifice_node = nodes.remove(sacrifice);
+            if sacrifice < target {
+                target -= 1;
+            }
+
+            nodes.insert(
+                target,
+                AnvilTree::Merge {
+                    target: Box::new(target_node),
+                    sacrifice: Box::new(sacrifice_node),
+                    cost: step.cost,
+                    result: step.result,
+                },
+            );
+        }
+
+        nodes.pop()
+    }
+
+    /// the items left after doing the first `completed` steps of the plan for `solution`.
+    /// each result takes the place of its target, and each sacrifice is used up.
+    pub fn items_after(&self, solution: Vec<Item>, completed: usize) -> Vec<Item> {
+        let mut items = solution.clone();
+
+        for step in self.steps(solution).into_iter().take(completed) {
+            let target = items.iter().position(|item| item == &step.target).unwrap();
+            items[target] = step.result;
+
+            let sacrifice = items
+                .iter()
+                .position(|item| item == &step.sacrifice)
+                .unwrap();
+            items.remove(sacrifice);
+        }
+
+        items
+    }
+
+    /// prices a plan the player built by hand. each pick is a target and a sacrifice, as indices into
+    /// the items left after the picks before it. the result takes the target's place.
+    /// returns `None` if an index is out of range or the items can't be combined.
+    pub fn evaluate(
+        &self,
+        source_items: Vec<Item>,
+        picks: &[(usize, usize)],
+    ) -> Option<ManualPlan> {
+        let mut plan = ManualPlan {
+            steps: Vec::new(),
+            remaining: source_items,
+        };
+
+        for &(target, sacrifice) in picks {
+            if target == sacrifice || target.max(sacrifice) >= plan.remaining.len() {
+                return None;
+            }
+
+            let (cost, result, rank, breakdown) = self.combine_explained(
+                plan.remaining[target].clone(),
+                plan.remaining[sacrifice].clone(),
+            )?;
+
+            plan.steps.push((
+                AnvilStep {
+                    target: plan.remaining[target].clone(),
+                    sacrifice: plan.remaining[sacrifice].clone(),
+                    cost,
+                    result: result.clone(),
+                    losses: breakdown.losses(),
+                },
+                rank,
+            ));
+
+            plan.remaining[target] = result;
+            plan.remaining.remove(sacrifice);
+        }
+
+        Some(plan)
     }
 
     /// given a vector of source items, this function checks all the possible ways to combine the items together.
     /// the function returns a struct containing information about the found results.
     pub fn combine_many(&self, source_items: Vec<Item>) -> AnvilCombinationResults {
+        self.combine_many_with_target(source_items, None)
+    }
+
+    /// like `combine_many`, but if `target` is given, only plans where the item at that index
+    /// is never used as a sacrifice are considered. it always ends up as the final target.
+    pub fn combine_many_with_target(
+        &self,
+        source_items: Vec<Item>,
+        target: Option<usize>,
+    ) -> AnvilCombinationResults {
         let mut lowest_cost = u32::MAX;
         let mut lowest_solution = Vec::new();
 
@@ -129,22 +521,44 @@ impl Anvil {
         let mut highest_cost = u32::MIN;
         let mut highest_solution = Vec::new();
 
-        let e_book_index = source_items
+        // tools have to stay the target whenever they meet a book, so the plans try every order of
+        // the tools and books, and every way of interleaving them that starts with a tool.
+        // the tool that ends up first becomes the final target.
+        let (tools, e_books): (Vec<_>, Vec<_>) = source_items
             .iter()
-            .position(|item| item.item_type() == &ItemType::EnchantedBook)
-            .unwrap_or(0);
+            .partition(|item| item.item_type() != &ItemType::EnchantedBook);
 
-        let items = &source_items[..e_book_index];
-        let item_permutations = items.into_iter().permutations(items.len());
+        let tool_permutations = tools.iter().permutations(tools.len());
+        let e_book_permutations = e_books.iter().permutations(e_books.len());
+        let interleavings = (1..source_items.len())
+            .combinations(tools.len().saturating_sub(1))
+            .collect_vec();
 
-        let e_books = &source_items[e_book_index..];
-        let e_book_permutations = e_books.into_iter().permutations(e_books.len());
-
-        for (permuted_items, permuted_books) in
-            item_permutations.cartesian_product(e_book_permutations)
+        'plans: for ((permuted_tools, permuted_books), tool_slots) in tool_permutations
+            .cartesian_product(e_book_permutations)
+            .cartesian_product(&interleavings)
         {
-            let mut items: Vec<_> = permuted_items.into_iter().cloned().collect();
-            items.extend(permuted_books.into_iter().cloned());
+            let mut permuted_tools = permuted_tools.into_iter();
+            let mut permuted_books = permuted_books.into_iter();
+            let order = (0..source_items.len())
+                .map(|slot| {
+                    let is_tool_slot = slot == 0 || tool_slots.contains(&slot);
+                    if is_tool_slot && !tools.is_empty() {
+                        *permuted_tools.next().unwrap()
+                    } else {
+                        *permuted_books.next().unwrap()
+                    }
+                })
+                .collect_vec();
+
+            // the first item is the only one that's never a sacrifice
+            if let Some(target) = target {
+                if !std::ptr::eq(order[0], &source_items[target]) {
+                    continue;
+                }
+            }
+
+            let mut items: Vec<Item> = order.into_iter().cloned().collect();
 
             let items_original = items.clone();
 
@@ -158,7 +572,10 @@ impl Anvil {
                     let item1 = items.remove(0);
                     let item2 = items.remove(0);
 
-                    let (cost, new_item, new_rank) = self.combine(item1, item2).unwrap();
+                    // a tool can't be put onto a book, so this order doesn't work
+                    let Some((cost, new_item, new_rank)) = self.combine(item1, item2) else {
+                        continue 'plans;
+                    };
 
                     if new_rank == AnvilCombinationRank::Flawed {
                         rank = AnvilCombinationRank::Flawed;
@@ -168,7 +585,7 @@ impl Anvil {
                     total_cost += cost;
                 }
 
-                if items.len() > 0 {
+                if !items.is_empty() {
                     new_items.push(items.remove(0));
                 }
 
@@ -214,16 +631,93 @@ impl Anvil {
             rank,
         }
     }
+
+    /// finds the fewest books that still give the same final item as using all of them, trying the
+    /// smallest sets of books first. returns `None` if every book is needed.
+    /// `target` is passed on to `combine_many_with_target`, and has to be a tool.
+    pub fn redundant_books(
+        &self,
+        source_items: Vec<Item>,
+        target: Option<usize>,
+    ) -> Option<RedundantBooks> {
+        let best = self.combine_many_with_target(source_items.clone(), target);
+        let best_item = self.tree(best.lowest_solution)?.item().clone();
+
+        let books = (0..source_items.len())
+            .filter(|&i| source_items[i].item_type() == &ItemType::EnchantedBook)
+            .collect_vec();
+
+        for kept in 0..books.len() {
+            let mut cheapest: Option<RedundantBooks> = None;
+
+            for kept_books in books.iter().combinations(kept) {
+                let left_out = books
+                    .iter()
+                    .copied()
+                    .filter(|book| !kept_books.contains(&book))
+                    .collect_vec();
+                let items = (0..source_items.len())
+                    .filter(|i| !left_out.contains(i))
+                    .map(|i| source_items[i].clone())
+                    .collect_vec();
+
+                // every enchantment needs an item with its level, or two to merge up to it,
+                // which is much quicker to check than solving
+                let reachable = best_item.enchantments().iter().all(|(enchantment, level)| {
+                    let levels = items.iter().filter_map(|item| item.level_of(*enchantment));
+                    levels.clone().count() > 1 || levels.max().is_some_and(|max| max >= *level)
+                });
+                if !reachable {
+                    continue;
+                }
+
+                // leaving out books before the target moves it forward
+                let target = target
+                    .map(|target| target - left_out.iter().filter(|book| **book < target).count());
+                let results = self.combine_many_with_target(items, target);
+                let Some(tree) = self.tree(results.lowest_solution) else {
+                    continue;
+                };
+
+                let enchantments = tree.item().enchantments();
+                let same_item = tree.item().item_type() == best_item.item_type()
+                    && enchantments.len() == best_item.enchantments().len()
+                    && enchantments
+                        .iter()
+                        .all(|enchantment| best_item.enchantments().contains(enchantment));
+
+                let levels_saved = best.lowest_cost.saturating_sub(results.lowest_cost);
+                if same_item
+                    && cheapest
+                        .as_ref()
+                        .is_none_or(|cheapest| levels_saved > cheapest.levels_saved)
+                {
+                    cheapest = Some(RedundantBooks {
+                        books: left_out,
+                        levels_saved,
+                    });
+                }
+            }
+
+            if cheapest.is_some() {
+                return cheapest;
+            }
+        }
+
+        None
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use crate::{
         enchantments::Enchantment,
-        item::{item, ItemType},
+        item::{item, ItemType, Material},
     };
 
-    use super::Anvil;
+    use super::{
+        Anvil, AnvilCombinationRank, AnvilTree, EnchantmentOutcome, LevelLoss, RedundantBooks,
+    };
 
     // tests are from https://minecraft.wiki/w/Anvil_mechanics#Costs_for_combining_enchantments
 
@@ -362,4 +856,373 @@ mod tests {
         assert_eq!(item.item_type(), &ItemType::Sword);
         assert_enchantments!(item, (Enchantment::Sharpness, 1), (Enchantment::Looting, 3));
     }
+
+    #[test]
+    fn edition_specific_conflicts() {
+        let crossbow = item!(ItemType::Crossbow, (Enchantment::Multishot, 1));
+        let book = item!(ItemType::EnchantedBook, (Enchantment::Piercing, 4));
+
+        // java discards piercing and charges a level for the conflict
+        let (cost, item, _) = Anvil::new_java()
+            .combine(crossbow.clone(), book.clone())
+            .unwrap();
+        assert_eq!(cost, 1);
+        assert_enchantments!(item, (Enchantment::Multishot, 1));
+
+        // bedrock keeps both
+        let (cost, item, _) = Anvil::new_bedrock().combine(crossbow, book).unwrap();
+        assert_eq!(cost, 4);
+        assert_enchantments!(
+            item,
+            (Enchantment::Multishot, 1),
+            (Enchantment::Piercing, 4)
+        );
+    }
+
+    #[test]
+    fn different_materials() {
+        let mut iron = item!(ItemType::Pickaxe, (Enchantment::Efficiency, 3));
+        iron.set_material(Material::Iron);
+        let diamond = item!(ItemType::Pickaxe, (Enchantment::Efficiency, 3));
+
+        assert!(Anvil::new_java().combine(iron.clone(), diamond).is_none());
+
+        let (_, item, _) = Anvil::new_java()
+            .combine(
+                iron,
+                item!(ItemType::EnchantedBook, (Enchantment::Unbreaking, 3)),
+            )
+            .unwrap();
+        assert_eq!(item.material(), Some(Material::Iron));
+    }
+
+    #[test]
+    fn multiple_tools() {
+        let mut used = item!(ItemType::Sword, (Enchantment::Looting, 3));
+        used.set_anvil_uses(2);
+        let fresh = item!(
+            ItemType::Sword,
+            (Enchantment::Sharpness, 5),
+            (Enchantment::Unbreaking, 3)
+        );
+
+        let anvil = Anvil::new_java();
+        let results = anvil.combine_many(vec![
+            fresh,
+            used.clone(),
+            item!(ItemType::EnchantedBook, (Enchantment::Mending, 1)),
+            item!(ItemType::EnchantedBook, (Enchantment::FireAspect, 2)),
+        ]);
+
+        // the sword with prior work becomes the target, since the result keeps its penalty either way
+        assert_eq!(results.lowest_cost, 30);
+        assert_eq!(results.lowest_solution[0], used);
+
+        let steps = anvil.steps(results.lowest_solution);
+        assert_eq!(steps.last().unwrap().result.enchantments().len(), 5);
+    }
+
+    #[test]
+    fn items_after_steps() {
+        let anvil = Anvil::new_java();
+        let solution = vec![
+            item!(ItemType::Pickaxe),
+            item!(ItemType::EnchantedBook, (Enchantment::Efficiency, 5)),
+            item!(ItemType::EnchantedBook, (Enchantment::Unbreaking, 3)),
+            item!(ItemType::EnchantedBook, (Enchantment::Mending, 1)),
+        ];
+
+        assert_eq!(anvil.items_after(solution.clone(), 0), solution);
+
+        let mut pickaxe = item!(ItemType::Pickaxe, (Enchantment::Efficiency, 5));
+        pickaxe.set_anvil_uses(1);
+        assert_eq!(
+            anvil.items_after(solution.clone(), 1),
+            vec![pickaxe, solution[2].clone(), solution[3].clone()]
+        );
+
+        let items = anvil.items_after(solution.clone(), 3);
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].enchantments().len(), 3);
+    }
+
+    #[test]
+    fn cost_breakdown() {
+        let mut sword = item!(ItemType::Sword, (Enchantment::Sharpness, 4));
+        sword.set_anvil_uses(1);
+        let book = item!(
+            ItemType::EnchantedBook,
+            (Enchantment::Sharpness, 4),
+            (Enchantment::Smite, 5),
+            (Enchantment::Looting, 3),
+            (Enchantment::Power, 5)
+        );
+
+        let (cost, _, _, breakdown) = Anvil::new_java()
+            .combine_explained(sword.clone(), book.clone())
+            .unwrap();
+        assert_eq!(cost, breakdown.total());
+        assert_eq!(breakdown.target_penalty, 1);
+        assert_eq!(breakdown.sacrifice_penalty, 0);
+
+        let outcomes = breakdown
+            .enchantments
+            .iter()
+            .map(|e| (e.enchantment, e.outcome, e.level, e.multiplier, e.cost))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            outcomes,
+            vec![
+                (
+                    Enchantment::Sharpness,
+                    EnchantmentOutcome::Merged { wasted: None },
+                    5,
+                    1,
+                    5
+                ),
+                (Enchantment::Smite, EnchantmentOutcome::Conflicting, 5, 1, 1),
+                (Enchantment::Looting, EnchantmentOutcome::Added, 3, 2, 6),
+                (
+                    Enchantment::Power,
+                    EnchantmentOutcome::Incompatible,
+                    5,
+                    1,
+                    0
+                ),
+            ]
+        );
+        assert!(breakdown.enchantments.iter().all(|e| e.from_book));
+
+        // bedrock doesn't charge for conflicts
+        let (cost, _, _, breakdown) = Anvil::new_bedrock().combine_explained(sword, book).unwrap();
+        assert_eq!(cost, breakdown.total());
+        assert_eq!(breakdown.enchantments[1].cost, 0);
+    }
+
+    #[test]
+    fn level_losses() {
+        let anvil = Anvil::new_java();
+        let solution = vec![
+            item!(
+                ItemType::Sword,
+                (Enchantment::Sharpness, 5),
+                (Enchantment::Looting, 2)
+            ),
+            item!(
+                ItemType::EnchantedBook,
+                (Enchantment::Smite, 5),
+                (Enchantment::Looting, 3),
+                (Enchantment::Unbreaking, 3)
+            ),
+        ];
+
+        let steps = anvil.steps(solution);
+        assert_eq!(
+            steps[0].losses,
+            vec![
+                LevelLoss::Conflicting {
+                    enchantment: Enchantment::Smite,
+                    level: 5
+                },
+                LevelLoss::Unmerged {
+                    enchantment: Enchantment::Looting,
+                    kept: 3,
+                    wasted: 2
+                },
+            ]
+        );
+
+        // nothing is lost when equal levels merge
+        let steps = anvil.steps(vec![
+            item!(ItemType::EnchantedBook, (Enchantment::Power, 4)),
+            item!(ItemType::EnchantedBook, (Enchantment::Power, 4)),
+        ]);
+        assert!(steps[0].losses.is_empty());
+    }
+
+    #[test]
+    fn over_max_levels() {
+        let mut book = item!(ItemType::EnchantedBook);
+        book.enchant_over_max(Enchantment::Sharpness, 10);
+        let sword = item!(ItemType::Sword);
+
+        // normally, levels from the sacrifice are brought down to the maximum
+        let (cost, item, _) = Anvil::new_java()
+            .combine(sword.clone(), book.clone())
+            .unwrap();
+        assert_eq!(item.level_of(Enchantment::Sharpness), Some(5));
+        assert_eq!(cost, 5);
+
+        // over-max mode only lets items hold higher levels, the game still caps merges
+        let anvil = Anvil::new_java().with_over_max(true);
+        let (cost, item, _) = anvil.combine(sword, book.clone()).unwrap();
+        assert_eq!(item.level_of(Enchantment::Sharpness), Some(5));
+        assert_eq!(cost, 5);
+
+        let (_, merged, rank) = anvil.combine(book.clone(), book).unwrap();
+        assert_eq!(merged.level_of(Enchantment::Sharpness), Some(5));
+        assert_eq!(rank, AnvilCombinationRank::Flawed);
+
+        // enchantments the sacrifice doesn't have are left alone
+        let mut max = item!(ItemType::EnchantedBook);
+        max.enchant_over_max(Enchantment::Sharpness, 255);
+        let (_, merged, _) = anvil
+            .combine(
+                max.clone(),
+                item!(ItemType::EnchantedBook, (Enchantment::Unbreaking, 1)),
+            )
+            .unwrap();
+        assert_eq!(merged.level_of(Enchantment::Sharpness), Some(255));
+
+        // bedrock charges the difference, which is nothing when the level goes down
+        let (cost, _, _) = Anvil::new_bedrock()
+            .combine(
+                max,
+                item!(ItemType::EnchantedBook, (Enchantment::Sharpness, 1)),
+            )
+            .unwrap();
+        assert_eq!(cost, 0);
+    }
+
+    #[test]
+    fn redundant_books() {
+        let anvil = Anvil::new_java();
+        let items = vec![
+            item!(ItemType::Pickaxe),
+            item!(ItemType::EnchantedBook, (Enchantment::Efficiency, 5)),
+            item!(ItemType::EnchantedBook, (Enchantment::Unbreaking, 3)),
+            item!(ItemType::EnchantedBook, (Enchantment::Efficiency, 5)),
+            item!(ItemType::EnchantedBook, (Enchantment::Unbreaking, 2)),
+        ];
+
+        let full = anvil.combine_many(items.clone()).lowest_cost;
+        let without = anvil
+            .combine_many(vec![items[0].clone(), items[1].clone(), items[2].clone()])
+            .lowest_cost;
+        assert_eq!(
+            anvil.redundant_books(items.clone(), Some(0)),
+            Some(RedundantBooks {
+                books: vec![3, 4],
+                levels_saved: full - without,
+            })
+        );
+
+        // two lower books are needed to merge into a higher level
+        let items = vec![
+            item!(ItemType::Pickaxe),
+            item!(ItemType::EnchantedBook, (Enchantment::Efficiency, 4)),
+            item!(ItemType::EnchantedBook, (Enchantment::Efficiency, 4)),
+        ];
+        assert_eq!(anvil.redundant_books(items, None), None);
+    }
+
+    #[test]
+    fn plan_tree() {
+        let anvil = Anvil::new_java();
+        let solution = vec![
+            item!(ItemType::Pickaxe),
+            item!(ItemType::EnchantedBook, (Enchantment::Efficiency, 5)),
+            item!(ItemType::EnchantedBook, (Enchantment::Unbreaking, 3)),
+        ];
+
+        let Some(AnvilTree::Merge {
+            target,
+            sacrifice,
+            cost,
+            result,
+        }) = anvil.tree(solution.clone())
+        else {
+            panic!("expected a merge at the root");
+        };
+
+        // the pickaxe and the first book are merged, then the second book is added
+        let steps = anvil.steps(solution.clone());
+        assert_eq!(cost, steps[1].cost);
+        assert_eq!(result, steps[1].result);
+        assert_eq!(*sacrifice, AnvilTree::Leaf(solution[2].clone()));
+        assert!(matches!(*target, AnvilTree::Merge { cost, .. } if cost == steps[0].cost));
+
+        assert_eq!(
+            anvil.tree(vec![solution[0].clone()]),
+            Some(AnvilTree::Leaf(solution[0].clone()))
+        );
+        assert_eq!(anvil.tree(vec![]), None);
+    }
+
+    #[test]
+    fn manual_plan() {
+        let anvil = Anvil::new_java();
+        let items = vec![
+            item!(ItemType::Pickaxe),
+            item!(ItemType::EnchantedBook, (Enchantment::Efficiency, 5)),
+            item!(ItemType::EnchantedBook, (Enchantment::Unbreaking, 3)),
+            item!(ItemType::EnchantedBook, (Enchantment::Mending, 1)),
+        ];
+
+        // book onto book first, then both onto the pickaxe
+        let plan = anvil
+            .evaluate(items.clone(), &[(1, 2), (0, 2), (0, 1)])
+            .unwrap();
+        assert!(plan.is_finished());
+        assert_eq!(plan.steps.len(), 3);
+        assert_eq!(plan.remaining[0].enchantments().len(), 3);
+        assert!(plan.steps.iter().all(|(step, rank)| {
+            !step.is_too_expensive() && rank == &AnvilCombinationRank::Perfect
+        }));
+        assert!(plan.total_cost() >= anvil.combine_many(items.clone()).lowest_cost);
+
+        // a partial plan keeps the leftover items
+        let plan = anvil.evaluate(items.clone(), &[(0, 3)]).unwrap();
+        assert!(!plan.is_finished());
+        assert_eq!(plan.remaining.len(), 3);
+
+        // a tool can't go onto a book, and picks have to exist
+        assert!(anvil.evaluate(items.clone(), &[(1, 0)]).is_none());
+        assert!(anvil.evaluate(items.clone(), &[(0, 4)]).is_none());
+        assert!(anvil.evaluate(items, &[(2, 2)]).is_none());
+
+        // the same two books lose a level when their levels differ
+        let plan = anvil
+            .evaluate(
+                vec![
+                    item!(ItemType::EnchantedBook, (Enchantment::Sharpness, 5)),
+                    item!(ItemType::EnchantedBook, (Enchantment::Sharpness, 4)),
+                ],
+                &[(0, 1)],
+            )
+            .unwrap();
+        assert_eq!(plan.steps[0].1, AnvilCombinationRank::Flawed);
+    }
+
+    #[test]
+    fn pinned_target() {
+        let mut used = item!(ItemType::Sword, (Enchantment::Looting, 3));
+        used.set_anvil_uses(2);
+        let fresh = item!(
+            ItemType::Sword,
+            (Enchantment::Sharpness, 5),
+            (Enchantment::Unbreaking, 3)
+        );
+        let items = vec![
+            used.clone(),
+            fresh.clone(),
+            item!(ItemType::EnchantedBook, (Enchantment::Mending, 1)),
+        ];
+
+        // left alone, the fresh sword takes the book before the used sword is put on top of it
+        let anvil = Anvil::new_java();
+        let unpinned = anvil.combine_many(items.clone());
+        assert_eq!(unpinned.lowest_cost, 18);
+        assert_eq!(unpinned.lowest_solution[0], fresh);
+
+        // pinning the used sword means every later step pays for its higher penalty
+        let results = anvil.combine_many_with_target(items, Some(0));
+        assert_eq!(results.lowest_cost, 23);
+        assert_eq!(results.lowest_solution[0], used);
+
+        // the pinned sword is never put on top of something else
+        for step in anvil.steps(results.lowest_solution) {
+            assert_ne!(step.sacrifice, used);
+        }
+    }
 }
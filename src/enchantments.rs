@@ -1,300 +1,321 @@
-use std::{collections::HashMap, fmt::Display, sync::LazyLock};
+use std::fmt::Display;
 
 use itertools::Itertools;
-use strum::EnumIter;
-
-use crate::{item::Item, util::prettify_pascal_case};
-
-#[derive(Copy, Clone, Debug, EnumIter, PartialEq, Eq, Hash)]
-pub enum Enchantment {
-    Protection,
-    FireProtection,
-    FeatherFalling,
-    BlastProtection,
-    ProjectileProtection,
-    Respiration,
-    AquaAffinity,
-    Thorns,
-    DepthStrider,
-    FrostWalker,
-    CurseOfBinding,
-    SoulSpeed,
-    SwiftSneak,
-    Sharpness,
-    Smite,
-    BaneOfArthropods,
-    Knockback,
-    FireAspect,
-    Looting,
-    SweepingEdge,
-    Efficiency,
-    SilkTouch,
-    Unbreaking,
-    Fortune,
-    Power,
-    Punch,
-    Flame,
-    Infinity,
-    LuckOfTheSea,
-    Lure,
-    Loyalty,
-    Impaling,
-    Riptide,
-    Channeling,
-    Multishot,
-    QuickCharge,
-    Piercing,
-    Density,
-    Breach,
-    WindBurst,
-    Mending,
-    CurseOfVanishing,
+use strum::{EnumIter, IntoEnumIterator};
+
+use crate::{
+    anvil::AnvilBehavior,
+    item::{Item, ItemType},
+    util::{prettify_pascal_case, to_roman_numerals},
+};
+
+/// the static properties of an enchantment.
+/// every enchantment has exactly one entry in the table at the bottom of this file.
+#[derive(Debug)]
+pub struct EnchantmentData {
+    /// the namespaced id of the enchantment, without the `minecraft:` prefix
+    pub id: &'static str,
+    /// the maximum level of the enchantment
+    pub max_level: u32,
+    /// the maximum level that can be obtained in survival gameplay
+    pub max_obtainable: u32,
+    /// the level multiplier on java edition, when the source item is not a book
+    pub java_multiplier: u32,
+    /// the level multiplier on bedrock edition, when the source item is not a book
+    pub bedrock_multiplier: u32,
+    /// the items this enchantment can be applied to in an anvil on java edition (books are implied)
+    pub java_items: &'static [ItemType],
+    /// the items this enchantment can be applied to in an anvil on bedrock edition (books are implied)
+    pub bedrock_items: &'static [ItemType],
+    /// the enchantments this one cannot be applied together with on java edition.
+    /// like the game's `exclusive_set` tags, this may contain the enchantment itself.
+    pub java_exclusive: &'static [Enchantment],
+    /// the enchantments this one cannot be applied together with on bedrock edition.
+    pub bedrock_exclusive: &'static [Enchantment],
+    /// whether this enchantment is a curse
+    pub curse: bool,
+    /// the index into a friendly ordering of enchantments
+    pub friendly_index: usize,
 }
 
-impl Enchantment {
-    const CONFLICTING_GROUPS: &'static [&'static [Self]] = &[
-        &[
-            Self::Protection,
-            Self::FireProtection,
-            Self::BlastProtection,
-            Self::ProjectileProtection,
-        ],
-        &[Self::DepthStrider, Self::FrostWalker],
-        &[
-            Self::Sharpness,
-            Self::Smite,
-            Self::BaneOfArthropods,
-            Self::Impaling,
-            Self::Density,
-            Self::Breach,
-        ],
-        &[Self::SilkTouch, Self::Fortune],
-        &[Self::Infinity, Self::Mending],
-        &[Self::Loyalty, Self::Riptide],
-        &[Self::Riptide, Self::Channeling],
-        &[Self::Multishot, Self::Piercing],
+/// picks the given value, or the default if there isn't one.
+macro_rules! or_default {
+    (; $default: expr) => {
+        $default
+    };
+    ($value: expr; $default: expr) => {
+        $value
+    };
+}
+
+/// declares the `Enchantment` enum along with its data table.
+/// the order of the entries is the order of the sprites in `public/enchantments.png`.
+macro_rules! enchantments {
+    ($(
+        $name: ident {
+            id: $id: expr,
+            max_level: $max_level: expr,
+            $(max_obtainable: $max_obtainable: expr,)?
+            multiplier: $multiplier: expr,
+            $(bedrock_multiplier: $bedrock_multiplier: expr,)?
+            items: $items: expr,
+            $(bedrock_items: $bedrock_items: expr,)?
+            $(exclusive: $exclusive: expr,)?
+            $(bedrock_exclusive: $bedrock_exclusive: expr,)?
+            $(curse: $curse: expr,)?
+            friendly_index: $friendly_index: expr $(,)?
+        },
+    )+) => {
+        #[derive(Copy, Clone, Debug, EnumIter, PartialEq, Eq, Hash)]
+        pub enum Enchantment {
+            $($name,)+
+        }
+
+        impl Enchantment {
+            /// returns the static data for this enchantment
+            pub fn data(&self) -> &'static EnchantmentData {
+                use groups::*;
+
+                match self {
+                    $(Self::$name => {
+                        const DATA: EnchantmentData = EnchantmentData {
+                            id: $id,
+                            max_level: $max_level,
+                            max_obtainable: or_default!($($max_obtainable)?; $max_level),
+                            java_multiplier: $multiplier,
+                            bedrock_multiplier: or_default!($($bedrock_multiplier)?; $multiplier),
+                            java_items: $items,
+                            bedrock_items: or_default!($($bedrock_items)?; $items),
+                            java_exclusive: or_default!($($exclusive)?; &[]),
+                            bedrock_exclusive: or_default!(
+                                $($bedrock_exclusive)?;
+                                or_default!($($exclusive)?; &[])
+                            ),
+                            curse: or_default!($($curse)?; false),
+                            friendly_index: $friendly_index,
+                        };
+                        &DATA
+                    })+
+                }
+            }
+        }
+    };
+}
+
+/// item and exclusivity groups used by the enchantment table.
+/// these mirror the `enchantable/*` and `exclusive_set/*` tags in the game's data.
+mod groups {
+    use super::Enchantment::{self, *};
+    use crate::item::ItemType::{self, *};
+
+    pub const HEAD_ARMOR: &[ItemType] = &[Helmet, TurtleHelmet];
+    pub const LEG_ARMOR: &[ItemType] = &[Leggings];
+    pub const FOOT_ARMOR: &[ItemType] = &[Boots];
+    pub const ARMOR: &[ItemType] = &[Helmet, TurtleHelmet, Chestplate, Leggings, Boots];
+    pub const EQUIPPABLE: &[ItemType] = &[
+        Helmet,
+        TurtleHelmet,
+        Chestplate,
+        Leggings,
+        Boots,
+        Elytra,
+        WolfArmor,
+        CarvedPumpkin,
+        MobHead,
+    ];
+
+    pub const SWORD: &[ItemType] = &[Sword];
+    pub const SHARP_WEAPON: &[ItemType] = &[Sword, Axe];
+    pub const WEAPON: &[ItemType] = &[Sword, Axe, Mace];
+    pub const FIRE_ASPECT: &[ItemType] = &[Sword, Mace];
+
+    pub const MINING: &[ItemType] = &[Pickaxe, Shovel, Axe, Hoe, Shears];
+    pub const MINING_LOOT: &[ItemType] = &[Pickaxe, Shovel, Axe, Hoe];
+
+    pub const BOW: &[ItemType] = &[Bow];
+    pub const CROSSBOW: &[ItemType] = &[Crossbow];
+    pub const TRIDENT: &[ItemType] = &[Trident];
+    pub const MACE: &[ItemType] = &[Mace];
+    pub const FISHING: &[ItemType] = &[FishingRod];
+
+    pub const DURABILITY: &[ItemType] = &[
+        Pickaxe,
+        Sword,
+        Axe,
+        Shovel,
+        Hoe,
+        Bow,
+        Crossbow,
+        Trident,
+        Mace,
+        Helmet,
+        TurtleHelmet,
+        Chestplate,
+        Leggings,
+        Boots,
+        Shield,
+        Elytra,
+        WolfArmor,
+        FishingRod,
+        Shears,
+        FlintAndSteel,
+        CarrotOnAStick,
+        WarpedFungusOnAStick,
+        Brush,
+    ];
+    pub const VANISHING: &[ItemType] = &[
+        Pickaxe,
+        Sword,
+        Axe,
+        Shovel,
+        Hoe,
+        Bow,
+        Crossbow,
+        Trident,
+        Mace,
+        Helmet,
+        TurtleHelmet,
+        Chestplate,
+        Leggings,
+        Boots,
+        Shield,
+        Elytra,
+        WolfArmor,
+        FishingRod,
+        Shears,
+        FlintAndSteel,
+        CarrotOnAStick,
+        WarpedFungusOnAStick,
+        Brush,
+        CarvedPumpkin,
+        MobHead,
+        Compass,
+        RecoveryCompass,
+    ];
+
+    pub const PROTECTION: &[Enchantment] = &[
+        Protection,
+        FireProtection,
+        BlastProtection,
+        ProjectileProtection,
+    ];
+    pub const BOOTS: &[Enchantment] = &[DepthStrider, FrostWalker];
+    pub const DAMAGE: &[Enchantment] = &[
+        Sharpness,
+        Smite,
+        BaneOfArthropods,
+        Impaling,
+        Density,
+        Breach,
     ];
+    pub const MINING_EXCLUSIVE: &[Enchantment] = &[SilkTouch, Fortune];
+    pub const BOW_EXCLUSIVE: &[Enchantment] = &[Infinity, Mending];
+    /// bedrock edition doesn't count impaling as a damage enchantment
+    pub const BEDROCK_DAMAGE: &[Enchantment] =
+        &[Sharpness, Smite, BaneOfArthropods, Density, Breach];
+    pub const RIPTIDE: &[Enchantment] = &[Loyalty, Channeling];
+    pub const CROSSBOW_EXCLUSIVE: &[Enchantment] = &[Multishot, Piercing];
+}
+
+impl Enchantment {
+    /// returns the namespaced id of this enchantment, e.g. `minecraft:sharpness`
+    pub fn id(&self) -> String {
+        format!("minecraft:{}", self.data().id)
+    }
+
+    /// finds the enchantment with the given id. the `minecraft:` namespace is optional.
+    pub fn from_id(id: &str) -> Option<Self> {
+        let id = id.strip_prefix("minecraft:").unwrap_or(id);
+
+        Self::iter().find(|enchantment| enchantment.data().id == id)
+    }
 
     /// returns the maximum level for the current enchantment
     pub fn max_level(&self) -> u32 {
-        match self {
-            Self::Sharpness
-            | Self::Smite
-            | Self::BaneOfArthropods
-            | Self::Efficiency
-            | Self::Power
-            | Self::Impaling
-            | Self::Density => 5,
-            Self::Protection
-            | Self::FireProtection
-            | Self::FeatherFalling
-            | Self::BlastProtection
-            | Self::ProjectileProtection
-            | Self::Piercing
-            | Self::Breach => 4,
-            Self::Respiration
-            | Self::Thorns
-            | Self::DepthStrider
-            | Self::SoulSpeed
-            | Self::SwiftSneak
-            | Self::Looting
-            | Self::SweepingEdge
-            | Self::Unbreaking
-            | Self::Fortune
-            | Self::LuckOfTheSea
-            | Self::Lure
-            | Self::Loyalty
-            | Self::Riptide
-            | Self::QuickCharge
-            | Self::WindBurst => 3,
-            Self::FrostWalker | Self::Knockback | Self::FireAspect | Self::Punch => 2,
-            Self::AquaAffinity
-            | Self::CurseOfBinding
-            | Self::SilkTouch
-            | Self::Flame
-            | Self::Infinity
-            | Self::Channeling
-            | Self::Multishot
-            | Self::Mending
-            | Self::CurseOfVanishing => 1,
-        }
+        self.data().max_level
     }
 
     /// returns the maximum level that can be obtained in survival gameplay
     pub fn max_obtainable(&self) -> u32 {
-        match self {
-            Self::WindBurst => 1,
-            _ => self.max_level(),
+        self.data().max_obtainable
+    }
+
+    /// returns the enchantments this one cannot be applied together with in the given edition
+    pub fn exclusive(&self, behavior: AnvilBehavior) -> &'static [Enchantment] {
+        match behavior {
+            AnvilBehavior::Java => self.data().java_exclusive,
+            AnvilBehavior::Bedrock => self.data().bedrock_exclusive,
+        }
+    }
+
+    /// returns the items this enchantment can be applied to in the given edition, excluding books
+    pub fn items(&self, behavior: AnvilBehavior) -> &'static [ItemType] {
+        match behavior {
+            AnvilBehavior::Java => self.data().java_items,
+            AnvilBehavior::Bedrock => self.data().bedrock_items,
         }
     }
 
+    /// returns `true` if this enchantment and the given one cannot be applied together.
+    /// an enchantment never conflicts with itself.
+    pub fn conflicts_with(&self, other: &Enchantment, behavior: AnvilBehavior) -> bool {
+        self != other
+            && (self.exclusive(behavior).contains(other)
+                || other.exclusive(behavior).contains(self))
+    }
+
     /// returns `true` if this enchantment conflicts with any of the given ones.
     /// conflicting enchantments means they cannot be applied together (e.g. Silk Touch and Fortune)
-    pub fn is_conflicting_with(&self, existing: &Vec<&Enchantment>) -> bool {
-        for group in Self::CONFLICTING_GROUPS {
-            // if this enchantment is in the group,
-            if group.contains(&self) {
-                // check if any of the existing enchantments are also in the group.
-                for enchantment in existing {
-                    if group.contains(enchantment) {
-                        // if they are, then the enchantments are conflicting.
-                        return true;
-                    }
-                }
-            }
-        }
+    pub fn is_conflicting_with(&self, existing: &[&Enchantment], behavior: AnvilBehavior) -> bool {
+        existing
+            .iter()
+            .any(|enchantment| self.conflicts_with(enchantment, behavior))
+    }
 
-        false
+    /// returns `true` if this enchantment can be applied to the given item type in an anvil
+    pub fn is_applicable_to(&self, item_type: &ItemType, behavior: AnvilBehavior) -> bool {
+        item_type == &ItemType::EnchantedBook || self.items(behavior).contains(item_type)
     }
 
     /// returns `true` if this enchantment is a curse
     pub fn is_curse(&self) -> bool {
-        self == &Self::CurseOfBinding || self == &Self::CurseOfVanishing
+        self.data().curse
     }
 
     /// the level multiplier for this enchantment on java edition.
     /// this value varies depending on if the source item is a book or not.
     pub fn java_multiplier(&self, from_book: bool) -> u32 {
-        let anvil_multiplier = match self {
-            Self::Protection
-            | Self::Sharpness
-            | Self::Efficiency
-            | Self::Power
-            | Self::Piercing => 1,
-            Self::FireProtection
-            | Self::FeatherFalling
-            | Self::ProjectileProtection
-            | Self::Smite
-            | Self::BaneOfArthropods
-            | Self::Knockback
-            | Self::Unbreaking
-            | Self::Loyalty
-            | Self::QuickCharge
-            | Self::Density => 2,
-            Self::BlastProtection
-            | Self::Respiration
-            | Self::AquaAffinity
-            | Self::DepthStrider
-            | Self::FrostWalker
-            | Self::FireAspect
-            | Self::Looting
-            | Self::SweepingEdge
-            | Self::Fortune
-            | Self::Punch
-            | Self::Flame
-            | Self::LuckOfTheSea
-            | Self::Lure
-            | Self::Impaling
-            | Self::Riptide
-            | Self::Multishot
-            | Self::Breach
-            | Self::WindBurst
-            | Self::Mending => 4,
-            Self::Thorns
-            | Self::CurseOfBinding
-            | Self::SoulSpeed
-            | Self::SwiftSneak
-            | Self::SilkTouch
-            | Self::Infinity
-            | Self::Channeling
-            | Self::CurseOfVanishing => 8,
-        };
-
-        if from_book {
-            (anvil_multiplier / 2).max(1)
-        } else {
-            anvil_multiplier
-        }
+        Self::book_multiplier(self.data().java_multiplier, from_book)
     }
 
     /// the level multiplier for this enchantment on bedrock platforms.
     /// this value varies depending on if the source item is a book or not.
     pub fn bedrock_multiplier(&self, from_book: bool) -> u32 {
-        let java_multipler = self.java_multiplier(from_book);
+        Self::book_multiplier(self.data().bedrock_multiplier, from_book)
+    }
 
-        if self == &Self::Loyalty || self == &Self::Impaling {
-            java_multipler / 2
+    /// books halve the multiplier, to a minimum of 1
+    fn book_multiplier(multiplier: u32, from_book: bool) -> u32 {
+        if from_book {
+            (multiplier / 2).max(1)
         } else {
-            java_multipler
+            multiplier
         }
     }
 
-    /// the index into a friendly ordering of enchantments
-    pub fn friendly_index(&self) -> usize {
-        use Enchantment::*;
-
-        macro_rules! map {
-            ($($ench: expr,)+) => {
-                LazyLock::new(#[allow(unused_assignments)] || {
-                    let mut map = HashMap::new();
-                    let mut i = 0;
-                    $(
-                        map.insert($ench, i);
-                        i += 1;
-                    )*
-                    map
-                })
-            };
+    /// the name of the enchantment with its level, like the game shows it.
+    /// the level is left out for enchantments that only have one level.
+    pub fn name_with_level(&self, level: u32) -> String {
+        if level == 1 && self.max_level() == 1 {
+            self.to_string()
+        } else {
+            format!("{self} {}", to_roman_numerals(level))
         }
+    }
 
-        static FRIENDLY: LazyLock<HashMap<Enchantment, usize>> = map![
-            // generic
-            Unbreaking,
-            Mending,
-            // tool
-            Efficiency,
-            Fortune,
-            SilkTouch,
-            // weapon
-            Sharpness,
-            Looting,
-            FireAspect,
-            Knockback,
-            SweepingEdge,
-            // bow
-            Power,
-            Infinity,
-            Flame,
-            Punch,
-            // armor
-            Protection,
-            FireProtection,
-            BlastProtection,
-            ProjectileProtection,
-            FeatherFalling,
-            Thorns,
-            // piece specific armor
-            AquaAffinity,
-            Respiration,
-            SwiftSneak,
-            DepthStrider,
-            SoulSpeed,
-            FrostWalker,
-            // mace
-            Density,
-            Breach,
-            WindBurst,
-            // trident
-            Impaling,
-            Loyalty,
-            Riptide,
-            Channeling,
-            // crossbow
-            Piercing,
-            QuickCharge,
-            Multishot,
-            // fishing rod
-            LuckOfTheSea,
-            Lure,
-            // rarely used
-            Smite,
-            BaneOfArthropods,
-            // curses
-            CurseOfBinding,
-            CurseOfVanishing,
-        ];
-
-        FRIENDLY[self]
+    /// the index into a friendly ordering of enchantments
+    pub fn friendly_index(&self) -> usize {
+        self.data().friendly_index
     }
 
     pub fn friendly_sort(
@@ -306,8 +327,9 @@ impl Enchantment {
     pub fn friendly_sort_with(
         iter: impl Iterator<Item = Enchantment>,
         item: &Item,
+        behavior: AnvilBehavior,
     ) -> impl Iterator<Item = Enchantment> {
-        Self::friendly_sort(iter).sorted_by_cached_key(|e| !item.is_compatible(&e))
+        Self::friendly_sort(iter).sorted_by_cached_key(move |e| !item.is_compatible(e, behavior))
     }
 }
 
@@ -317,16 +339,467 @@ impl Display for Enchantment {
     }
 }
 
+enchantments! {
+    Protection {
+        id: "protection",
+        max_level: 4,
+        multiplier: 1,
+        items: ARMOR,
+        exclusive: PROTECTION,
+        friendly_index: 14,
+    },
+    FireProtection {
+        id: "fire_protection",
+        max_level: 4,
+        multiplier: 2,
+        items: ARMOR,
+        exclusive: PROTECTION,
+        friendly_index: 15,
+    },
+    FeatherFalling {
+        id: "feather_falling",
+        max_level: 4,
+        multiplier: 2,
+        items: FOOT_ARMOR,
+        friendly_index: 18,
+    },
+    BlastProtection {
+        id: "blast_protection",
+        max_level: 4,
+        multiplier: 4,
+        items: ARMOR,
+        exclusive: PROTECTION,
+        friendly_index: 16,
+    },
+    ProjectileProtection {
+        id: "projectile_protection",
+        max_level: 4,
+        multiplier: 2,
+        items: ARMOR,
+        exclusive: PROTECTION,
+        friendly_index: 17,
+    },
+    Respiration {
+        id: "respiration",
+        max_level: 3,
+        multiplier: 4,
+        items: HEAD_ARMOR,
+        friendly_index: 21,
+    },
+    AquaAffinity {
+        id: "aqua_affinity",
+        max_level: 1,
+        multiplier: 4,
+        items: HEAD_ARMOR,
+        friendly_index: 20,
+    },
+    Thorns {
+        id: "thorns",
+        max_level: 3,
+        multiplier: 8,
+        items: ARMOR,
+        friendly_index: 19,
+    },
+    DepthStrider {
+        id: "depth_strider",
+        max_level: 3,
+        multiplier: 4,
+        items: FOOT_ARMOR,
+        exclusive: BOOTS,
+        friendly_index: 23,
+    },
+    FrostWalker {
+        id: "frost_walker",
+        max_level: 2,
+        multiplier: 4,
+        items: FOOT_ARMOR,
+        exclusive: BOOTS,
+        friendly_index: 25,
+    },
+    CurseOfBinding {
+        id: "binding_curse",
+        max_level: 1,
+        multiplier: 8,
+        items: EQUIPPABLE,
+        curse: true,
+        friendly_index: 40,
+    },
+    SoulSpeed {
+        id: "soul_speed",
+        max_level: 3,
+        multiplier: 8,
+        items: FOOT_ARMOR,
+        friendly_index: 24,
+    },
+    SwiftSneak {
+        id: "swift_sneak",
+        max_level: 3,
+        multiplier: 8,
+        items: LEG_ARMOR,
+        friendly_index: 22,
+    },
+    Sharpness {
+        id: "sharpness",
+        max_level: 5,
+        multiplier: 1,
+        items: SHARP_WEAPON,
+        exclusive: DAMAGE,
+        bedrock_exclusive: BEDROCK_DAMAGE,
+        friendly_index: 5,
+    },
+    Smite {
+        id: "smite",
+        max_level: 5,
+        multiplier: 2,
+        items: WEAPON,
+        exclusive: DAMAGE,
+        bedrock_exclusive: BEDROCK_DAMAGE,
+        friendly_index: 38,
+    },
+    BaneOfArthropods {
+        id: "bane_of_arthropods",
+        max_level: 5,
+        multiplier: 2,
+        items: WEAPON,
+        exclusive: DAMAGE,
+        bedrock_exclusive: BEDROCK_DAMAGE,
+        friendly_index: 39,
+    },
+    Knockback {
+        id: "knockback",
+        max_level: 2,
+        multiplier: 2,
+        items: SWORD,
+        friendly_index: 8,
+    },
+    FireAspect {
+        id: "fire_aspect",
+        max_level: 2,
+        multiplier: 4,
+        items: FIRE_ASPECT,
+        friendly_index: 7,
+    },
+    Looting {
+        id: "looting",
+        max_level: 3,
+        multiplier: 4,
+        items: SWORD,
+        friendly_index: 6,
+    },
+    SweepingEdge {
+        id: "sweeping_edge",
+        max_level: 3,
+        multiplier: 4,
+        items: SWORD,
+        // sweeping edge only exists on java edition
+        bedrock_items: &[],
+        friendly_index: 9,
+    },
+    Efficiency {
+        id: "efficiency",
+        max_level: 5,
+        multiplier: 1,
+        items: MINING,
+        friendly_index: 2,
+    },
+    SilkTouch {
+        id: "silk_touch",
+        max_level: 1,
+        multiplier: 8,
+        items: MINING_LOOT,
+        exclusive: MINING_EXCLUSIVE,
+        friendly_index: 4,
+    },
+    Unbreaking {
+        id: "unbreaking",
+        max_level: 3,
+        multiplier: 2,
+        items: DURABILITY,
+        friendly_index: 0,
+    },
+    Fortune {
+        id: "fortune",
+        max_level: 3,
+        multiplier: 4,
+        items: MINING_LOOT,
+        exclusive: MINING_EXCLUSIVE,
+        friendly_index: 3,
+    },
+    Power {
+        id: "power",
+        max_level: 5,
+        multiplier: 1,
+        items: BOW,
+        friendly_index: 10,
+    },
+    Punch {
+        id: "punch",
+        max_level: 2,
+        multiplier: 4,
+        items: BOW,
+        friendly_index: 13,
+    },
+    Flame {
+        id: "flame",
+        max_level: 1,
+        multiplier: 4,
+        items: BOW,
+        friendly_index: 12,
+    },
+    Infinity {
+        id: "infinity",
+        max_level: 1,
+        multiplier: 8,
+        items: BOW,
+        exclusive: BOW_EXCLUSIVE,
+        friendly_index: 11,
+    },
+    LuckOfTheSea {
+        id: "luck_of_the_sea",
+        max_level: 3,
+        multiplier: 4,
+        items: FISHING,
+        friendly_index: 36,
+    },
+    Lure {
+        id: "lure",
+        max_level: 3,
+        multiplier: 4,
+        items: FISHING,
+        friendly_index: 37,
+    },
+    Loyalty {
+        id: "loyalty",
+        max_level: 3,
+        multiplier: 2,
+        bedrock_multiplier: 1,
+        items: TRIDENT,
+        exclusive: &[Enchantment::Riptide],
+        friendly_index: 30,
+    },
+    Impaling {
+        id: "impaling",
+        max_level: 5,
+        multiplier: 4,
+        bedrock_multiplier: 2,
+        items: TRIDENT,
+        exclusive: DAMAGE,
+        bedrock_exclusive: &[],
+        friendly_index: 29,
+    },
+    Riptide {
+        id: "riptide",
+        max_level: 3,
+        multiplier: 4,
+        items: TRIDENT,
+        exclusive: RIPTIDE,
+        friendly_index: 31,
+    },
+    Channeling {
+        id: "channeling",
+        max_level: 1,
+        multiplier: 8,
+        items: TRIDENT,
+        exclusive: &[Enchantment::Riptide],
+        friendly_index: 32,
+    },
+    Multishot {
+        id: "multishot",
+        max_level: 1,
+        multiplier: 4,
+        items: CROSSBOW,
+        exclusive: CROSSBOW_EXCLUSIVE,
+        bedrock_exclusive: &[],
+        friendly_index: 35,
+    },
+    QuickCharge {
+        id: "quick_charge",
+        max_level: 3,
+        multiplier: 2,
+        items: CROSSBOW,
+        friendly_index: 34,
+    },
+    Piercing {
+        id: "piercing",
+        max_level: 4,
+        multiplier: 1,
+        items: CROSSBOW,
+        exclusive: CROSSBOW_EXCLUSIVE,
+        bedrock_exclusive: &[],
+        friendly_index: 33,
+    },
+    Density {
+        id: "density",
+        max_level: 5,
+        multiplier: 2,
+        items: MACE,
+        exclusive: DAMAGE,
+        bedrock_exclusive: BEDROCK_DAMAGE,
+        friendly_index: 26,
+    },
+    Breach {
+        id: "breach",
+        max_level: 4,
+        multiplier: 4,
+        items: MACE,
+        exclusive: DAMAGE,
+        bedrock_exclusive: BEDROCK_DAMAGE,
+        friendly_index: 27,
+    },
+    WindBurst {
+        id: "wind_burst",
+        max_level: 3,
+        max_obtainable: 1,
+        multiplier: 4,
+        items: MACE,
+        friendly_index: 28,
+    },
+    Mending {
+        id: "mending",
+        max_level: 1,
+        multiplier: 4,
+        items: DURABILITY,
+        exclusive: BOW_EXCLUSIVE,
+        friendly_index: 1,
+    },
+    CurseOfVanishing {
+        id: "vanishing_curse",
+        max_level: 1,
+        multiplier: 8,
+        items: VANISHING,
+        curse: true,
+        friendly_index: 41,
+    },
+}
+
 #[cfg(test)]
 mod test {
+    use std::collections::HashSet;
+
     use strum::IntoEnumIterator;
 
     use super::Enchantment;
+    use crate::{anvil::AnvilBehavior, item::ItemType};
 
     #[test]
     fn all_enchantments_are_friendly() {
-        Enchantment::iter().for_each(|e| {
-            println!("{} => {}", e, e.friendly_index());
-        });
+        let indices: HashSet<_> = Enchantment::iter().map(|e| e.friendly_index()).collect();
+
+        // every enchantment has its own index, with no gaps
+        assert_eq!(indices.len(), Enchantment::iter().count());
+        assert!(indices.iter().all(|i| *i < indices.len()));
+    }
+
+    #[test]
+    fn enchantment_data_is_consistent() {
+        for enchantment in Enchantment::iter() {
+            let data = enchantment.data();
+
+            assert!((1..=5).contains(&data.max_level), "{enchantment:?}");
+            assert!(
+                (1..=data.max_level).contains(&data.max_obtainable),
+                "{enchantment:?}"
+            );
+
+            for multiplier in [data.java_multiplier, data.bedrock_multiplier] {
+                assert!([1, 2, 4, 8].contains(&multiplier), "{enchantment:?}");
+            }
+            assert!(
+                data.bedrock_multiplier <= data.java_multiplier,
+                "{enchantment:?}"
+            );
+
+            for behavior in [AnvilBehavior::Java, AnvilBehavior::Bedrock] {
+                // books are always compatible, so they are never listed
+                // only sweeping edge is missing from bedrock
+                let items = enchantment.items(behavior);
+                assert_eq!(
+                    items.is_empty(),
+                    behavior == AnvilBehavior::Bedrock && enchantment == Enchantment::SweepingEdge,
+                    "{enchantment:?}"
+                );
+                assert!(!items.contains(&ItemType::EnchantedBook), "{enchantment:?}");
+                assert_eq!(items.iter().collect::<HashSet<_>>().len(), items.len());
+
+                // exclusivity must be declared on both sides
+                for other in enchantment.exclusive(behavior) {
+                    assert!(
+                        other == &enchantment || other.exclusive(behavior).contains(&enchantment),
+                        "{enchantment:?} is exclusive with {other:?} on {behavior:?}, but not the other way around"
+                    );
+                }
+                assert!(!enchantment.conflicts_with(&enchantment, behavior));
+            }
+
+            assert_eq!(Enchantment::from_id(&enchantment.id()), Some(enchantment));
+            assert!(data.id.chars().all(|c| c.is_ascii_lowercase() || c == '_'));
+
+            // curses only have a single level, and are named as such
+            assert_eq!(
+                data.curse,
+                format!("{enchantment:?}").starts_with("CurseOf")
+            );
+            if data.curse {
+                assert_eq!(data.max_level, 1);
+            }
+        }
+    }
+
+    #[test]
+    fn enchantment_compatibility() {
+        use Enchantment::*;
+        use ItemType::*;
+
+        let java = AnvilBehavior::Java;
+        let bedrock = AnvilBehavior::Bedrock;
+
+        assert!(FrostWalker.is_applicable_to(&Boots, java));
+        assert!(!FrostWalker.is_applicable_to(&Helmet, java));
+        assert!(CurseOfBinding.is_applicable_to(&Elytra, java));
+        assert!(!CurseOfBinding.is_applicable_to(&Shield, java));
+        assert!(Unbreaking.is_applicable_to(&Shield, java));
+        assert!(!Thorns.is_applicable_to(&Shield, java));
+        assert!(Efficiency.is_applicable_to(&Shears, java));
+        assert!(!SilkTouch.is_applicable_to(&Shears, java));
+        assert!(Sharpness.is_applicable_to(&Axe, java));
+        assert!(Sharpness.is_applicable_to(&Axe, bedrock));
+        assert!(SweepingEdge.is_applicable_to(&Sword, java));
+        assert!(!SweepingEdge.is_applicable_to(&Sword, bedrock));
+
+        assert!(Respiration.is_applicable_to(&TurtleHelmet, java));
+        assert!(Protection.is_applicable_to(&TurtleHelmet, java));
+        assert!(Mending.is_applicable_to(&Brush, java));
+        assert!(CurseOfBinding.is_applicable_to(&CarvedPumpkin, java));
+        assert!(CurseOfBinding.is_applicable_to(&MobHead, java));
+        assert!(!Unbreaking.is_applicable_to(&MobHead, java));
+        assert!(CurseOfVanishing.is_applicable_to(&Compass, java));
+        assert!(!CurseOfBinding.is_applicable_to(&RecoveryCompass, java));
+
+        assert!(Sharpness.conflicts_with(&Smite, java));
+        assert!(Riptide.conflicts_with(&Loyalty, java));
+        assert!(Channeling.conflicts_with(&Riptide, java));
+        assert!(!Loyalty.conflicts_with(&Channeling, java));
+        assert!(Mending.conflicts_with(&Infinity, java));
+        assert!(Mending.conflicts_with(&Infinity, bedrock));
+
+        // bedrock is more lenient with crossbows and tridents
+        assert!(Multishot.conflicts_with(&Piercing, java));
+        assert!(!Multishot.conflicts_with(&Piercing, bedrock));
+        assert!(Impaling.conflicts_with(&Sharpness, java));
+        assert!(!Impaling.conflicts_with(&Sharpness, bedrock));
+        assert!(Sharpness.conflicts_with(&Density, bedrock));
+    }
+
+    #[test]
+    fn enchantment_names() {
+        assert_eq!(Enchantment::Mending.name_with_level(1), "Mending");
+        assert_eq!(Enchantment::Mending.name_with_level(2), "Mending II");
+        assert_eq!(Enchantment::Sharpness.name_with_level(1), "Sharpness I");
+        assert_eq!(Enchantment::Sharpness.name_with_level(4), "Sharpness IV");
+        assert_eq!(Enchantment::Sharpness.name_with_level(10), "Sharpness X");
+        assert_eq!(
+            Enchantment::Sharpness.name_with_level(11),
+            "Sharpness enchantment.level.11"
+        );
     }
 }
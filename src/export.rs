@@ -0,0 +1,255 @@
+//! exports items and combination plans as commands, so they can be reproduced in game.
+
+use itertools::Itertools;
+
+use crate::{
+    anvil::Anvil,
+    item::{Item, ItemType},
+};
+
+/// the pack format of the generated datapack (1.21.5)
+const PACK_FORMAT: u32 = 71;
+
+/// creates a `/give` command for the item, using the data component syntax from 1.21.5 onwards.
+/// ```
+/// let item = item!(ItemType::Sword, (Enchantment::Sharpness, 5));
+/// assert_eq!(
+///     give_command(&item),
+///     r#"give @s minecraft:diamond_sword[minecraft:enchantments={"minecraft:sharpness":5}]"#
+/// );
+/// ```
+pub fn give_command(item: &Item) -> String {
+    let mut components = Vec::new();
+
+    if !item.enchantments().is_empty() {
+        let component = if item.item_type() == &ItemType::EnchantedBook {
+            "stored_enchantments"
+        } else {
+            "enchantments"
+        };
+        let levels = item
+            .enchantments()
+            .iter()
+            .map(|(enchantment, level)| format!("\"{}\":{level}", enchantment.id()))
+            .join(",");
+
+        components.push(format!("minecraft:{component}={{{levels}}}"));
+    }
+
+    if item.work_penalty() > 0 {
+        components.push(format!("minecraft:repair_cost={}", item.work_penalty()));
+    }
+
+    if components.is_empty() {
+        format!("give @s {}", item.id())
+    } else {
+        format!("give @s {}[{}]", item.id(), components.join(","))
+    }
+}
+
+/// creates an `.mcfunction` file that gives the player everything needed to follow a plan from `combine_many`:
+/// the source items, and enough levels to pay for every step.
+pub fn mcfunction(anvil: &Anvil, solution: &[Item]) -> String {
+    let steps = anvil.steps(solution.to_vec());
+    let total_cost: u32 = steps.iter().map(|step| step.cost).sum();
+
+    let mut lines = vec!["# generated by the anvil calculator".to_string()];
+    if let Some(last) = steps.last() {
+        lines.push(format!(
+            "# creates a {} for {total_cost} levels",
+            describe(&last.result)
+        ));
+    }
+    for (i, step) in steps.iter().enumerate() {
+        lines.push(format!(
+            "# step {}: {} + {} costs {} levels",
+            i + 1,
+            describe(&step.target),
+            describe(&step.sacrifice),
+            step.cost
+        ));
+    }
+
+    lines.push(String::new());
+    lines.extend(solution.iter().map(give_command));
+    lines.push(format!("xp add @s {total_cost} levels"));
+
+    lines.join("\n") + "\n"
+}
+
+/// the item's name followed by its enchantments, e.g. `Enchanted Book (Sharpness V, Mending)`
+fn describe(item: &Item) -> String {
+    if item.enchantments().is_empty() {
+        return item.to_string();
+    }
+
+    let enchantments = item
+        .enchantments()
+        .iter()
+        .map(|(enchantment, level)| enchantment.name_with_level(*level))
+        .collect::<Vec<_>>()
+        .join(", ");
+    format!("{item} ({enchantments})")
+}
+
+/// creates a datapack zip containing the plan as the function `anvil:plan`
+pub fn datapack(anvil: &Anvil, solution: &[Item]) -> Vec<u8> {
+    let pack_mcmeta = format!(
+        "{{\"pack\":{{\"pack_format\":{PACK_FORMAT},\"description\":\"anvil calculator plan\"}}}}\n"
+    );
+
+    zip(&[
+        ("pack.mcmeta", pack_mcmeta.as_bytes()),
+        (
+            "data/anvil/function/plan.mcfunction",
+            mcfunction(anvil, solution).as_bytes(),
+        ),
+    ])
+}
+
+/// creates an uncompressed zip archive
+fn zip(files: &[(&str, &[u8])]) -> Vec<u8> {
+    // 1980-01-01, the earliest date zip files can store
+    const DATE: u16 = 0x21;
+
+    let mut archive = Vec::new();
+    let mut central_directory = Vec::new();
+
+    for (name, contents) in files {
+        let offset = archive.len() as u32;
+        let crc = crc32(contents);
+
+        // the local header and central directory share most of their fields
+        let mut fields = Vec::new();
+        fields.extend(20u16.to_le_bytes()); // version needed to extract
+        fields.extend(0u16.to_le_bytes()); // flags
+        fields.extend(0u16.to_le_bytes()); // compression method (stored)
+        fields.extend(0u16.to_le_bytes()); // modification time
+        fields.extend(DATE.to_le_bytes()); // modification date
+        fields.extend(crc.to_le_bytes());
+        fields.extend((contents.len() as u32).to_le_bytes()); // compressed size
+        fields.extend((contents.len() as u32).to_le_bytes()); // uncompressed size
+        fields.extend((name.len() as u16).to_le_bytes());
+        fields.extend(0u16.to_le_bytes()); // extra field length
+
+        archive.extend(0x04034b50u32.to_le_bytes());
+        archive.extend(&fields);
+        archive.extend(name.as_bytes());
+        archive.extend(*contents);
+
+        central_directory.extend(0x02014b50u32.to_le_bytes());
+        central_directory.extend(20u16.to_le_bytes()); // version made by
+        central_directory.extend(&fields);
+        central_directory.extend(0u16.to_le_bytes()); // comment length
+        central_directory.extend(0u16.to_le_bytes()); // disk number
+        central_directory.extend(0u16.to_le_bytes()); // internal attributes
+        central_directory.extend(0u32.to_le_bytes()); // external attributes
+        central_directory.extend(offset.to_le_bytes());
+        central_directory.extend(name.as_bytes());
+    }
+
+    let central_directory_offset = archive.len() as u32;
+    archive.extend(&central_directory);
+
+    archive.extend(0x06054b50u32.to_le_bytes());
+    archive.extend(0u16.to_le_bytes()); // disk number
+    archive.extend(0u16.to_le_bytes()); // disk with the central directory
+    archive.extend((files.len() as u16).to_le_bytes());
+    archive.extend((files.len() as u16).to_le_bytes());
+    archive.extend((central_directory.len() as u32).to_le_bytes());
+    archive.extend(central_directory_offset.to_le_bytes());
+    archive.extend(0u16.to_le_bytes()); // comment length
+
+    archive
+}
+
+fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = !0u32;
+
+    for byte in bytes {
+        crc ^= *byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ 0xedb88320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+
+    !crc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{crc32, give_command, mcfunction};
+    use crate::{
+        anvil::Anvil,
+        enchantments::Enchantment,
+        import::parse_item,
+        item::{item, ItemType},
+    };
+
+    #[test]
+    fn give_commands() {
+        let mut sword = item!(
+            ItemType::Sword,
+            (Enchantment::Sharpness, 5),
+            (Enchantment::Looting, 3)
+        );
+        sword.set_anvil_uses(2);
+
+        let command = give_command(&sword);
+        assert_eq!(
+            command,
+            r#"give @s minecraft:diamond_sword[minecraft:enchantments={"minecraft:sharpness":5,"minecraft:looting":3},minecraft:repair_cost=3]"#
+        );
+
+        // exported commands can be imported again
+        assert_eq!(parse_item(&command), Ok(sword));
+
+        let book = item!(ItemType::EnchantedBook, (Enchantment::Mending, 1));
+        assert_eq!(
+            give_command(&book),
+            r#"give @s minecraft:enchanted_book[minecraft:stored_enchantments={"minecraft:mending":1}]"#
+        );
+        assert_eq!(
+            give_command(&item!(ItemType::Elytra)),
+            "give @s minecraft:elytra"
+        );
+    }
+
+    #[test]
+    fn plan_mcfunction() {
+        let solution = vec![
+            item!(ItemType::Pickaxe),
+            item!(ItemType::EnchantedBook, (Enchantment::Efficiency, 5)),
+            item!(ItemType::EnchantedBook, (Enchantment::Unbreaking, 3)),
+        ];
+
+        let function = mcfunction(&Anvil::new_java(), &solution);
+        assert!(function
+            .contains("# step 1: Diamond Pickaxe + Enchanted Book (Efficiency V) costs 5 levels"));
+
+        let lines: Vec<_> = function
+            .lines()
+            .filter(|line| !line.starts_with('#'))
+            .collect();
+
+        assert_eq!(
+            lines,
+            [
+                "",
+                "give @s minecraft:diamond_pickaxe",
+                r#"give @s minecraft:enchanted_book[minecraft:stored_enchantments={"minecraft:efficiency":5}]"#,
+                r#"give @s minecraft:enchanted_book[minecraft:stored_enchantments={"minecraft:unbreaking":3}]"#,
+                "xp add @s 9 levels",
+            ]
+        );
+    }
+
+    #[test]
+    fn crc() {
+        assert_eq!(crc32(b"123456789"), 0xcbf43926);
+    }
+}
@@ -0,0 +1,508 @@
+//! imports items from the formats the game uses, such as `/give` commands and item nbt.
+
+use crate::{
+    enchantments::Enchantment,
+    item::{Item, MAX_ANVIL_USES},
+    lang::Translations,
+    nbt::{
+        binary::{self, NbtError},
+        snbt::{self, Reader, SnbtError},
+        Tag,
+    },
+};
+
+#[derive(Debug, PartialEq)]
+pub enum ImportError {
+    /// the input isn't valid command or snbt syntax
+    Syntax(SnbtError),
+    /// the file isn't a valid nbt file
+    Nbt(NbtError),
+    /// the item id isn't one the calculator knows about
+    UnknownItem(String),
+    /// the enchantment id isn't one the calculator knows about
+    UnknownEnchantment(String),
+    /// a value has the wrong type, e.g. a string where a level should be
+    InvalidValue(&'static str),
+    /// the repair cost can't be reached by using the item in an anvil, or needs more than `MAX_ANVIL_USES`
+    UnreachableRepairCost(i64),
+}
+
+impl ImportError {
+    /// describes the error in the given language
+    pub fn message(&self, translations: &Translations) -> String {
+        match self {
+            Self::Syntax(error) => translations.format(
+                "calculator.import.error.syntax",
+                &[&(error.position + 1).to_string()],
+            ),
+            Self::Nbt(_) => translations.get("calculator.import.error.nbt"),
+            Self::UnknownItem(id) => {
+                translations.format("calculator.import.error.unknown_item", &[id])
+            }
+            Self::UnknownEnchantment(id) => {
+                translations.format("calculator.import.error.unknown_enchantment", &[id])
+            }
+            Self::InvalidValue(name) => translations.get(&format!(
+                "calculator.import.error.invalid.{}",
+                name.replace(' ', "_")
+            )),
+            Self::UnreachableRepairCost(cost) => {
+                translations.format("calculator.import.error.repair_cost", &[&cost.to_string()])
+            }
+        }
+    }
+}
+
+impl From<SnbtError> for ImportError {
+    fn from(error: SnbtError) -> Self {
+        Self::Syntax(error)
+    }
+}
+
+impl From<NbtError> for ImportError {
+    fn from(error: NbtError) -> Self {
+        Self::Nbt(error)
+    }
+}
+
+/// parses an item from a `/give` command or an snbt item compound.
+/// both the data component syntax from 1.20.5 onwards and the older nbt syntax are supported.
+/// ```
+/// let item = parse_item("/give @p diamond_sword[enchantments={levels:{sharpness:5}},repair_cost=3]")?;
+/// assert_eq!(item.level_of(Enchantment::Sharpness), Some(5));
+///
+/// let item = parse_item("diamond_sword{Enchantments:[{id:\"sharpness\",lvl:5s}],RepairCost:3}")?;
+/// assert_eq!(item.work_penalty(), 3);
+/// ```
+pub fn parse_item(input: &str) -> Result<Item, ImportError> {
+    let input = input.trim();
+    let input = input.strip_prefix('/').unwrap_or(input);
+
+    // an item compound, like the ones from `/data get`
+    if input.starts_with('{') {
+        return item_from_nbt(&snbt::parse(input)?);
+    }
+
+    let mut reader = Reader::new(input);
+    if input.starts_with("give ") {
+        reader.read_while(|c| !c.is_whitespace());
+        skip_target(&mut reader);
+    }
+
+    let id = reader.read_resource_location()?;
+    let mut item = Item::from_id(id).ok_or(ImportError::UnknownItem(id.to_string()))?;
+
+    match reader.peek() {
+        Some('[') => apply_components(&mut item, &read_components(&mut reader)?)?,
+        Some('{') => apply_legacy_tag(&mut item, &reader.read_value()?)?,
+        _ => {}
+    }
+
+    // the item count is irrelevant to the anvil
+    reader.skip_whitespace();
+    reader.read_while(|c| c.is_ascii_digit());
+
+    reader.skip_whitespace();
+    if reader.is_done() {
+        Ok(item)
+    } else {
+        Err(reader.error("end of command").into())
+    }
+}
+
+/// creates an item from its nbt, as stored in inventories and world saves
+pub fn item_from_nbt(tag: &Tag) -> Result<Item, ImportError> {
+    let id = tag
+        .get("id")
+        .and_then(Tag::as_str)
+        .ok_or(ImportError::InvalidValue("item id"))?;
+    let mut item = Item::from_id(id).ok_or(ImportError::UnknownItem(id.to_string()))?;
+
+    if let Some(components) = tag.get("components") {
+        apply_components(&mut item, components)?;
+    }
+    if let Some(legacy_tag) = tag.get("tag") {
+        apply_legacy_tag(&mut item, legacy_tag)?;
+    }
+
+    Ok(item)
+}
+
+/// reads the items in a player's inventory and ender chest from a world save.
+/// this accepts `playerdata/<uuid>.dat` files, and `level.dat` files from singleplayer worlds.
+/// items the calculator doesn't know about, such as blocks, are left out, and so are items
+/// without enchantments or prior work. returns the items along with how many slots couldn't be read.
+pub fn items_from_save(bytes: &[u8]) -> Result<(Vec<Item>, usize), ImportError> {
+    let root = binary::read(bytes)?;
+
+    // in singleplayer, the player is stored inside `level.dat`
+    let player = root
+        .get("Data")
+        .and_then(|data| data.get("Player"))
+        .unwrap_or(&root);
+
+    let inventories = ["Inventory", "EnderItems"]
+        .into_iter()
+        .filter_map(|key| player.get(key).and_then(Tag::as_list))
+        .collect::<Vec<_>>();
+    if inventories.is_empty() {
+        return Err(ImportError::InvalidValue("player data"));
+    }
+
+    let mut items = Vec::new();
+    let mut skipped = 0;
+    for tag in inventories.into_iter().flatten() {
+        match item_from_nbt(tag) {
+            Ok(item) if item.enchantments().is_empty() && item.anvil_uses() == 0 => {}
+            Ok(item) => items.push(item),
+            Err(ImportError::UnknownItem(_)) => {}
+            // one bad slot shouldn't stop the rest of the inventory from loading
+            Err(_) => skipped += 1,
+        }
+    }
+
+    Ok((items, skipped))
+}
+
+/// skips over the player name or target selector in a command
+fn skip_target(reader: &mut Reader) {
+    reader.skip_whitespace();
+    reader.read_while(|c| !c.is_whitespace() && c != '[');
+
+    // selectors can have arguments, such as `@a[distance=..5]`
+    if reader.peek() == Some('[') {
+        let mut depth = 0;
+        reader.read_while(|c| {
+            let inside = depth > 0;
+            match c {
+                '[' => depth += 1,
+                ']' => depth -= 1,
+                _ => {}
+            }
+            inside || c == '['
+        });
+    }
+}
+
+/// reads a list of data components, such as `[enchantments={levels:{sharpness:5}},!repair_cost]`
+fn read_components(reader: &mut Reader) -> Result<Tag, SnbtError> {
+    reader.expect('[', "'['")?;
+    let mut components = Vec::new();
+
+    if !reader.eat(']') {
+        loop {
+            // removed components don't matter, since the item starts out with nothing
+            let removed = reader.eat('!');
+            let key = reader.read_resource_location()?;
+
+            if !removed {
+                reader.expect('=', "'='")?;
+                let value = reader.read_value()?;
+                components.push((key.to_string(), value));
+            }
+
+            if reader.eat(']') {
+                break;
+            }
+            reader.expect(',', "',' or ']'")?;
+        }
+    }
+
+    Ok(Tag::Compound(components))
+}
+
+/// applies the data components used by 1.20.5 and later
+fn apply_components(item: &mut Item, components: &Tag) -> Result<(), ImportError> {
+    for key in ["enchantments", "stored_enchantments"] {
+        let Some(enchantments) = components.get(key) else {
+            continue;
+        };
+
+        // before 1.21.5, levels were nested inside a `levels` compound
+        let levels = enchantments
+            .get("levels")
+            .unwrap_or(enchantments)
+            .as_compound()
+            .ok_or(ImportError::InvalidValue("enchantments"))?;
+
+        for (id, level) in levels {
+            let level = level
+                .as_int()
+                .ok_or(ImportError::InvalidValue("enchantment level"))?;
+            enchant(item, id, level)?;
+        }
+    }
+
+    if let Some(repair_cost) = components.get("repair_cost") {
+        set_repair_cost(item, repair_cost)?;
+    }
+
+    Ok(())
+}
+
+/// applies the nbt tag used before 1.20.5
+fn apply_legacy_tag(item: &mut Item, tag: &Tag) -> Result<(), ImportError> {
+    for key in ["Enchantments", "StoredEnchantments"] {
+        let Some(enchantments) = tag.get(key) else {
+            continue;
+        };
+
+        for enchantment in enchantments
+            .as_list()
+            .ok_or(ImportError::InvalidValue("enchantments"))?
+        {
+            let id = enchantment
+                .get("id")
+                .and_then(Tag::as_str)
+                .ok_or(ImportError::InvalidValue("enchantment id"))?;
+            let level = enchantment
+                .get("lvl")
+                .and_then(Tag::as_int)
+                .ok_or(ImportError::InvalidValue("enchantment level"))?;
+
+            enchant(item, id, level)?;
+        }
+    }
+
+    if let Some(repair_cost) = tag.get("RepairCost") {
+        set_repair_cost(item, repair_cost)?;
+    }
+
+    Ok(())
+}
+
+fn enchant(item: &mut Item, id: &str, level: i64) -> Result<(), ImportError> {
+    // sweeping edge was called sweeping before 1.20.5
+    let enchantment = match id.strip_prefix("minecraft:").unwrap_or(id) {
+        "sweeping" => Some(Enchantment::SweepingEdge),
+        _ => Enchantment::from_id(id),
+    }
+    .ok_or(ImportError::UnknownEnchantment(id.to_string()))?;
+
+    // levels of 0 or below do nothing in game. levels above the max are kept, since commands can make them
+    if level > 0 {
+        item.enchant_over_max(enchantment, level.min(u32::MAX as i64) as u32);
+    }
+
+    Ok(())
+}
+
+fn set_repair_cost(item: &mut Item, repair_cost: &Tag) -> Result<(), ImportError> {
+    let repair_cost = repair_cost
+        .as_int()
+        .ok_or(ImportError::InvalidValue("repair cost"))?;
+
+    // the repair cost doubles and adds one with each use, so it is always one less than a power of two
+    match repair_cost.checked_add(1).map(u32::try_from) {
+        Some(Ok(cost)) if cost.is_power_of_two() && cost.ilog2() <= MAX_ANVIL_USES => {
+            item.set_anvil_uses(cost.ilog2());
+            Ok(())
+        }
+        _ => Err(ImportError::UnreachableRepairCost(repair_cost)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{items_from_save, parse_item, ImportError};
+    use crate::{
+        enchantments::Enchantment,
+        item::{ItemType, Material},
+        lang::Language,
+    };
+
+    #[test]
+    fn parse_components() {
+        let item =
+            parse_item("/give @p diamond_sword[enchantments={levels:{sharpness:5}},repair_cost=3]")
+                .unwrap();
+
+        assert_eq!(item.item_type(), &ItemType::Sword);
+        assert_eq!(item.material(), Some(Material::Diamond));
+        assert_eq!(item.level_of(Enchantment::Sharpness), Some(5));
+        assert_eq!(item.work_penalty(), 3);
+
+        // 1.21.5 dropped the `levels` compound
+        let item = parse_item(
+            r#"give @a[distance=..5] minecraft:enchanted_book[minecraft:stored_enchantments={"minecraft:mending":1,unbreaking:3}] 2"#,
+        )
+        .unwrap();
+
+        assert_eq!(item.item_type(), &ItemType::EnchantedBook);
+        assert_eq!(item.level_of(Enchantment::Mending), Some(1));
+        assert_eq!(item.level_of(Enchantment::Unbreaking), Some(3));
+        assert_eq!(item.work_penalty(), 0);
+
+        // levels over the max are kept, up to the highest the game stores
+        let item = parse_item("/give @p netherite_sword[enchantments={sharpness:10,looting:1000}]")
+            .unwrap();
+        assert_eq!(item.level_of(Enchantment::Sharpness), Some(10));
+        assert_eq!(item.level_of(Enchantment::Looting), Some(255));
+    }
+
+    #[test]
+    fn parse_legacy_nbt() {
+        let item = parse_item(
+            r#"/give Steve iron_pickaxe{Enchantments:[{id:"minecraft:efficiency",lvl:4s},{id:"sweeping",lvl:1s}],RepairCost:7}"#,
+        )
+        .unwrap();
+
+        assert_eq!(item.material(), Some(Material::Iron));
+        assert_eq!(item.level_of(Enchantment::Efficiency), Some(4));
+        assert_eq!(item.level_of(Enchantment::SweepingEdge), Some(1));
+        assert_eq!(item.work_penalty(), 7);
+
+        let item = parse_item(
+            r#"{id:"minecraft:enchanted_book",Count:1b,tag:{StoredEnchantments:[{id:"minecraft:fortune",lvl:3s}]}}"#,
+        )
+        .unwrap();
+        assert_eq!(item.level_of(Enchantment::Fortune), Some(3));
+    }
+
+    #[test]
+    fn parse_item_compound() {
+        let item = parse_item(
+            r#"{id:"minecraft:netherite_boots",count:1,components:{"minecraft:enchantments":{levels:{"minecraft:soul_speed":3}},"minecraft:repair_cost":1}}"#,
+        )
+        .unwrap();
+
+        assert_eq!(item.item_type(), &ItemType::Boots);
+        assert_eq!(item.material(), Some(Material::Netherite));
+        assert_eq!(item.level_of(Enchantment::SoulSpeed), Some(3));
+        assert_eq!(item.work_penalty(), 1);
+    }
+
+    #[test]
+    fn import_errors() {
+        assert_eq!(
+            parse_item("/give @p dirt"),
+            Err(ImportError::UnknownItem("dirt".to_string()))
+        );
+        assert_eq!(
+            parse_item("diamond_sword[enchantments={levels:{sharpest:5}}]"),
+            Err(ImportError::UnknownEnchantment("sharpest".to_string()))
+        );
+        assert_eq!(
+            parse_item("diamond_sword[repair_cost=4]"),
+            Err(ImportError::UnreachableRepairCost(4))
+        );
+        assert_eq!(
+            parse_item("diamond_sword[repair_cost=63]").map(|item| item.anvil_uses()),
+            Ok(6)
+        );
+        assert_eq!(
+            parse_item("diamond_sword[repair_cost=127]"),
+            Err(ImportError::UnreachableRepairCost(127))
+        );
+        assert_eq!(
+            parse_item("diamond_sword[repair_cost=2147483647]"),
+            Err(ImportError::UnreachableRepairCost(2147483647))
+        );
+        assert_eq!(
+            parse_item("diamond_sword[repair_cost=9223372036854775807L]"),
+            Err(ImportError::UnreachableRepairCost(i64::MAX))
+        );
+        assert!(matches!(
+            parse_item("diamond_sword[repair_cost=3"),
+            Err(ImportError::Syntax(_))
+        ));
+
+        let english = Language::EnglishUs.translations();
+        assert_eq!(
+            parse_item("diamond_sword[repair_cost=3")
+                .unwrap_err()
+                .message(&english),
+            "invalid syntax at character 28"
+        );
+        for name in [
+            "item id",
+            "player data",
+            "enchantments",
+            "enchantment id",
+            "enchantment level",
+            "repair cost",
+        ] {
+            let message = ImportError::InvalidValue(name).message(&english);
+            assert!(!message.starts_with("calculator."), "{name} has no message");
+        }
+    }
+
+    #[test]
+    fn read_save() {
+        fn string(s: &str) -> Vec<u8> {
+            [&(s.len() as u16).to_be_bytes()[..], s.as_bytes()].concat()
+        }
+        fn named(id: u8, name: &str, payload: Vec<u8>) -> Vec<u8> {
+            [vec![id], string(name), payload].concat()
+        }
+
+        // {Inventory:[{id:"minecraft:enchanted_book",components:{"minecraft:stored_enchantments":{levels:{"minecraft:mending":1}}}},{id:"minecraft:dirt"},...]}
+        let book = [
+            named(8, "id", string("minecraft:enchanted_book")),
+            named(
+                10,
+                "components",
+                [
+                    named(
+                        10,
+                        "minecraft:stored_enchantments",
+                        [
+                            named(
+                                10,
+                                "levels",
+                                [
+                                    named(3, "minecraft:mending", 1i32.to_be_bytes().to_vec()),
+                                    vec![0],
+                                ]
+                                .concat(),
+                            ),
+                            vec![0],
+                        ]
+                        .concat(),
+                    ),
+                    vec![0],
+                ]
+                .concat(),
+            ),
+            vec![0],
+        ]
+        .concat();
+        let dirt = [named(8, "id", string("minecraft:dirt")), vec![0]].concat();
+        // {id:"minecraft:diamond_sword"}, which has nothing to combine
+        let sword = [named(8, "id", string("minecraft:diamond_sword")), vec![0]].concat();
+        // {id:"minecraft:bow",tag:{RepairCost:4}}, which can't be read
+        let bow = [
+            named(8, "id", string("minecraft:bow")),
+            named(
+                10,
+                "tag",
+                [named(3, "RepairCost", 4i32.to_be_bytes().to_vec()), vec![0]].concat(),
+            ),
+            vec![0],
+        ]
+        .concat();
+        let inventory = [
+            vec![10],
+            4i32.to_be_bytes().to_vec(),
+            book,
+            dirt,
+            sword,
+            bow,
+        ]
+        .concat();
+        let file = [named(10, "", named(9, "Inventory", inventory)), vec![0]].concat();
+
+        let (items, skipped) = items_from_save(&file).unwrap();
+        assert_eq!(skipped, 1);
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].item_type(), &ItemType::EnchantedBook);
+        assert_eq!(items[0].level_of(Enchantment::Mending), Some(1));
+
+        // files without a player are rejected
+        let empty = [named(10, "", Vec::new()), vec![0]].concat();
+        assert_eq!(
+            items_from_save(&empty),
+            Err(ImportError::InvalidValue("player data"))
+        );
+    }
+}
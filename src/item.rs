@@ -2,9 +2,9 @@ use itertools::Itertools;
 use std::fmt::Display;
 use strum::{EnumIter, IntoEnumIterator};
 
-use crate::{enchantments::Enchantment, util::prettify_pascal_case};
+use crate::{anvil::AnvilBehavior, enchantments::Enchantment, util::prettify_pascal_case};
 
-#[derive(Clone, Copy, Debug, EnumIter, PartialEq)]
+#[derive(Clone, Copy, Debug, EnumIter, PartialEq, Eq, Hash)]
 pub enum ItemType {
     EnchantedBook,
 
@@ -23,11 +23,13 @@ pub enum ItemType {
 
     // armour
     Helmet,
+    TurtleHelmet,
     Chestplate,
     Leggings,
     Boots,
     Shield,
     Elytra,
+    WolfArmor,
 
     // uncommon
     FishingRod,
@@ -35,11 +37,51 @@ pub enum ItemType {
     FlintAndSteel,
     CarrotOnAStick,
     WarpedFungusOnAStick,
+    Brush,
+
+    // curses only
+    CarvedPumpkin,
+    MobHead,
+    Compass,
+    RecoveryCompass,
 }
 
 impl ItemType {
-    pub fn is_armor(&self) -> bool {
-        [Self::Helmet, Self::Chestplate, Self::Leggings, Self::Boots].contains(self)
+    /// the namespaced id of this item type, without a material. mob heads use `minecraft:player_head`.
+    pub fn id(&self) -> String {
+        match self {
+            // mob heads have an id per mob, so pick the one anyone can get
+            ItemType::MobHead => "minecraft:player_head".to_string(),
+            item_type => format!(
+                "minecraft:{}",
+                item_type.to_string().to_lowercase().replace(' ', "_")
+            ),
+        }
+    }
+
+    /// finds the item type with the given id, which may include a material (e.g. `diamond_sword`).
+    /// the `minecraft:` namespace is optional.
+    #[cfg(feature = "serde")]
+    pub fn from_id(id: &str) -> Option<Self> {
+        let namespaced = format!("minecraft:{}", id.strip_prefix("minecraft:").unwrap_or(id));
+
+        ItemType::iter()
+            .find(|item_type| item_type.id() == namespaced)
+            .or_else(|| Item::from_id(id).map(|item| item.item_type))
+    }
+
+    /// the materials this item can be made out of. empty if the item only comes in one variant.
+    pub fn materials(&self) -> &'static [Material] {
+        use ItemType::*;
+        use Material::*;
+
+        match self {
+            Pickaxe | Sword | Axe | Shovel | Hoe => &[Wood, Stone, Iron, Gold, Diamond, Netherite],
+            Helmet | Chestplate | Leggings | Boots => {
+                &[Leather, Chainmail, Iron, Gold, Diamond, Netherite]
+            }
+            _ => &[],
+        }
     }
 
     pub fn rarity(&self) -> Rarity {
@@ -48,6 +90,7 @@ impl ItemType {
 
         match self {
             EnchantedBook => Book,
+            MobHead | RecoveryCompass => Uncommon,
             Trident => Rare,
             Elytra | Mace => Epic,
             _ => Common,
@@ -55,7 +98,87 @@ impl ItemType {
     }
 }
 
-#[allow(dead_code)]
+/// the material a tool or piece of armour is made out of
+#[derive(Clone, Copy, Debug, EnumIter, PartialEq, Eq, Hash)]
+pub enum Material {
+    Wood,
+    Leather,
+    Stone,
+    Chainmail,
+    Iron,
+    Gold,
+    Diamond,
+    Netherite,
+}
+
+impl Material {
+    /// the item used to repair items of this material in an anvil
+    pub fn repair_item(&self) -> &'static str {
+        match self {
+            Self::Wood => "Planks",
+            Self::Leather => "Leather",
+            Self::Stone => "Cobblestone",
+            Self::Chainmail | Self::Iron => "Iron Ingot",
+            Self::Gold => "Gold Ingot",
+            Self::Diamond => "Diamond",
+            Self::Netherite => "Netherite Ingot",
+        }
+    }
+
+    /// the durability of tools made out of this material
+    fn tool_durability(&self) -> u32 {
+        match self {
+            Self::Wood => 59,
+            Self::Stone => 131,
+            Self::Iron => 250,
+            Self::Gold => 32,
+            Self::Diamond => 1561,
+            Self::Netherite => 2031,
+            Self::Leather | Self::Chainmail => 0,
+        }
+    }
+
+    /// the multiplier applied to the base durability of armour pieces
+    fn armor_durability_multiplier(&self) -> u32 {
+        match self {
+            Self::Leather => 5,
+            Self::Chainmail | Self::Iron => 15,
+            Self::Gold => 7,
+            Self::Diamond => 33,
+            Self::Netherite => 37,
+            Self::Wood | Self::Stone => 0,
+        }
+    }
+
+    /// the enchantability of tools and armour made out of this material
+    fn enchantability(&self, armor: bool) -> u32 {
+        match (self, armor) {
+            (Self::Wood, _) => 15,
+            (Self::Leather, _) => 15,
+            (Self::Stone, _) => 5,
+            (Self::Chainmail, _) => 12,
+            (Self::Iron, false) => 14,
+            (Self::Iron, true) => 9,
+            (Self::Gold, false) => 22,
+            (Self::Gold, true) => 25,
+            (Self::Diamond, _) => 10,
+            (Self::Netherite, _) => 15,
+        }
+    }
+}
+
+impl Display for Material {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Self::Wood => "Wooden",
+            Self::Gold => "Golden",
+            _ => return write!(f, "{self:?}"),
+        };
+
+        write!(f, "{name}")
+    }
+}
+
 pub enum Rarity {
     Common,
     Uncommon,
@@ -86,10 +209,18 @@ impl Rarity {
     }
 }
 
+/// the most anvil uses an item can have. past this, the work penalty alone makes any use too expensive.
+pub const MAX_ANVIL_USES: u32 = 6;
+
+/// the highest enchantment level the game can store, which can be reached with commands
+pub const OVER_MAX_LEVEL: u32 = 255;
+
 #[derive(Clone, Debug, PartialEq)]
 pub struct Item {
     /// the type of item this is (e.g. book, pickaxe, etc)
     item_type: ItemType,
+    /// the material this item is made out of, if its type comes in several materials
+    material: Option<Material>,
     /// the amount of times this item has been used in an anvil already
     anvil_uses: u32,
     /// the enchantments and their corresponding levels
@@ -97,9 +228,18 @@ pub struct Item {
 }
 
 impl Item {
+    /// creates an unenchanted item.
+    /// items that come in several materials are made out of diamond by default.
     pub fn new(item_type: ItemType) -> Self {
+        let material = if item_type.materials().is_empty() {
+            None
+        } else {
+            Some(Material::Diamond)
+        };
+
         Self {
             item_type,
+            material,
             anvil_uses: 0,
             enchantments: Vec::new(),
         }
@@ -109,11 +249,148 @@ impl Item {
         &self.item_type
     }
 
+    pub fn material(&self) -> Option<Material> {
+        self.material
+    }
+
+    /// changes the material of this item.
+    /// this does nothing if the item can't be made out of the given material.
+    pub fn set_material(&mut self, material: Material) {
+        if self.item_type.materials().contains(&material) {
+            self.material = Some(material);
+        }
+    }
+
+    /// checks if the two items are the same kind of item, which is required to combine them.
+    /// for example, an iron pickaxe can't be combined with a diamond pickaxe.
+    pub fn is_same_kind(&self, other: &Item) -> bool {
+        self.item_type == other.item_type && self.material == other.material
+    }
+
+    /// returns the namespaced id of this item, e.g. `minecraft:diamond_sword`
+    pub fn id(&self) -> String {
+        let id = self.item_type.id();
+
+        match self.material {
+            Some(material) => id.replacen(
+                "minecraft:",
+                &format!("minecraft:{}_", material.to_string().to_lowercase()),
+                1,
+            ),
+            None => id,
+        }
+    }
+
+    /// creates an unenchanted item from the given id. the `minecraft:` namespace is optional.
+    pub fn from_id(id: &str) -> Option<Self> {
+        const HEADS: &[&str] = &[
+            "player_head",
+            "zombie_head",
+            "creeper_head",
+            "skeleton_skull",
+            "wither_skeleton_skull",
+            "dragon_head",
+            "piglin_head",
+        ];
+
+        let id = id.strip_prefix("minecraft:").unwrap_or(id);
+        if HEADS.contains(&id) {
+            return Some(Self::new(ItemType::MobHead));
+        }
+
+        ItemType::iter()
+            .flat_map(|item_type| {
+                let mut variants = vec![Self::new(item_type)];
+                for material in item_type.materials() {
+                    let mut item = Self::new(item_type);
+                    item.set_material(*material);
+                    variants.push(item);
+                }
+                variants
+            })
+            .find(|item| item.id().strip_prefix("minecraft:") == Some(id))
+    }
+
+    /// the maximum durability of this item, or `None` if it can't be damaged
+    pub fn max_durability(&self) -> Option<u32> {
+        use ItemType::*;
+
+        let armor_base = match self.item_type {
+            Helmet => 11,
+            Chestplate => 16,
+            Leggings => 15,
+            Boots => 13,
+            _ => 0,
+        };
+
+        match (self.item_type, self.material) {
+            (_, Some(material)) if armor_base > 0 => {
+                Some(armor_base * material.armor_durability_multiplier())
+            }
+            (_, Some(material)) => Some(material.tool_durability()),
+            (Bow, _) => Some(384),
+            (Crossbow, _) => Some(465),
+            (Trident, _) => Some(250),
+            (Mace, _) => Some(500),
+            (TurtleHelmet, _) => Some(275),
+            (Shield, _) => Some(336),
+            (Elytra, _) => Some(432),
+            (WolfArmor, _) => Some(64),
+            (FishingRod, _) => Some(64),
+            (Shears, _) => Some(238),
+            (FlintAndSteel, _) => Some(64),
+            (CarrotOnAStick, _) => Some(25),
+            (WarpedFungusOnAStick, _) => Some(100),
+            (Brush, _) => Some(64),
+            _ => None,
+        }
+    }
+
+    /// the enchantability of this item, used when rolling enchantments in an enchanting table.
+    /// returns `None` if the item can't be enchanted in an enchanting table.
+    pub fn enchantability(&self) -> Option<u32> {
+        use ItemType::*;
+
+        match (self.item_type, self.material) {
+            (Helmet | Chestplate | Leggings | Boots, Some(material)) => {
+                Some(material.enchantability(true))
+            }
+            (_, Some(material)) => Some(material.enchantability(false)),
+            (EnchantedBook | Bow | Crossbow | Trident | FishingRod, _) => Some(1),
+            (Mace, _) => Some(15),
+            (TurtleHelmet, _) => Some(9),
+            _ => None,
+        }
+    }
+
+    /// the item used to repair this item in an anvil, if there is one
+    pub fn repair_item(&self) -> Option<&'static str> {
+        use ItemType::*;
+
+        match (self.item_type, self.material) {
+            (_, Some(material)) => Some(material.repair_item()),
+            (Shield, _) => Some("Planks"),
+            (Elytra, _) => Some("Phantom Membrane"),
+            (TurtleHelmet, _) => Some("Turtle Scute"),
+            (Mace, _) => Some("Breeze Rod"),
+            (WolfArmor, _) => Some("Armadillo Scute"),
+            _ => None,
+        }
+    }
+
     /// calculates the work penalty of this item using the number of anvil uses
     pub fn work_penalty(&self) -> u32 {
         2u32.pow(self.anvil_uses) - 1
     }
 
+    pub fn anvil_uses(&self) -> u32 {
+        self.anvil_uses
+    }
+
+    pub fn set_anvil_uses(&mut self, anvil_uses: u32) {
+        self.anvil_uses = anvil_uses;
+    }
+
     pub fn increment_anvil_uses(&mut self) {
         self.anvil_uses += 1;
     }
@@ -144,8 +421,16 @@ impl Item {
     /// assert_eq!(item.level_of(Enchantment::Efficiency), Some(1));
     /// ```
     pub fn enchant(&mut self, enchantment: Enchantment, level: u32) {
-        let level = u32::min(level, enchantment.max_level());
+        self.set_level(enchantment, u32::min(level, enchantment.max_level()));
+    }
+
+    /// like `enchant`, but the level is only reduced to `OVER_MAX_LEVEL`, the highest level the game can store.
+    /// items like this can only be made with commands.
+    pub fn enchant_over_max(&mut self, enchantment: Enchantment, level: u32) {
+        self.set_level(enchantment, u32::min(level, OVER_MAX_LEVEL));
+    }
 
+    fn set_level(&mut self, enchantment: Enchantment, level: u32) {
         for (i, existing_enchantment) in self.enchantments.iter_mut().enumerate() {
             if existing_enchantment.0 == enchantment {
                 existing_enchantment.1 = level;
@@ -161,6 +446,42 @@ impl Item {
         self.enchantments.push((enchantment, level));
     }
 
+    pub fn clear_enchantments(&mut self) {
+        self.enchantments.clear();
+    }
+
+    /// raises every enchantment that can go on `item_type` to its maximum level, skipping curses
+    /// and anything that conflicts with an enchantment the item already has. levels already
+    /// above the maximum are left as they are.
+    /// enchantments are added in their friendly order, so the most useful of each conflicting group is picked.
+    pub fn max_enchantments(&mut self, item_type: &ItemType, behavior: AnvilBehavior) {
+        let enchantments = Enchantment::friendly_sort(Enchantment::iter().filter(|enchantment| {
+            !enchantment.is_curse()
+                && enchantment.is_applicable_to(item_type, behavior)
+                && self.is_compatible(enchantment, behavior)
+        }))
+        .collect_vec();
+
+        for enchantment in enchantments {
+            if !self.has_conflict(&enchantment, behavior) {
+                let level = self.level_of(enchantment).unwrap_or(0);
+                self.enchant_over_max(enchantment, level.max(enchantment.max_level()));
+            }
+        }
+    }
+
+    /// splits the item's enchantments into unused books with one enchantment each
+    pub fn split_into_books(&self) -> Vec<Item> {
+        self.enchantments
+            .iter()
+            .map(|(enchantment, level)| {
+                let mut book = Item::new(ItemType::EnchantedBook);
+                book.enchant_over_max(*enchantment, *level);
+                book
+            })
+            .collect()
+    }
+
     /// gets the level of the given enchantment, or `None` if the item doesn't have it.
     pub fn level_of(&self, wanted_enchantment: Enchantment) -> Option<u32> {
         for enchantment in &self.enchantments {
@@ -175,86 +496,27 @@ impl Item {
     /// checks if the given enchantment is conflicting with the item.
     /// conflicting means that the enchantment is conflicting with another enchantment.
     /// for example, the Fortune and Silk Touch enchantments conflict with each other.
-    pub fn has_conflict(&self, enchantment: &Enchantment) -> bool {
-        !enchantment.is_conflicting_with(&self.enchantments.iter().map(|(e, _)| e).collect())
+    pub fn has_conflict(&self, enchantment: &Enchantment, behavior: AnvilBehavior) -> bool {
+        enchantment.is_conflicting_with(
+            &self.enchantments.iter().map(|(e, _)| e).collect_vec(),
+            behavior,
+        )
     }
 
     /// checks if the given enchantment is compatible with the item.
     /// for example, Silk Touch is compatible with pickaxes, but not with swords.
-    pub fn is_compatible(&self, enchantment: &Enchantment) -> bool {
-        if self.item_type == ItemType::EnchantedBook {
-            true
-        } else {
-            self.compatible_enchantments().contains(enchantment)
-        }
+    pub fn is_compatible(&self, enchantment: &Enchantment, behavior: AnvilBehavior) -> bool {
+        enchantment.is_applicable_to(&self.item_type, behavior)
     }
 
-    pub fn compatible_enchantments(&self) -> impl Iterator<Item = Enchantment> {
-        use Enchantment::*;
-        use ItemType::*;
-
-        let mut enchants = Vec::new();
-        if self.item_type == EnchantedBook {
-            enchants.extend(Enchantment::iter());
-        } else {
-            enchants.push(Unbreaking);
-            enchants.push(Mending);
-            enchants.push(CurseOfVanishing);
-        }
+    pub fn compatible_enchantments(
+        &self,
+        behavior: AnvilBehavior,
+    ) -> impl Iterator<Item = Enchantment> {
+        let item_type = self.item_type;
 
-        enchants.extend(match self.item_type {
-            EnchantedBook => Vec::new(),
-            Pickaxe | Shovel | Hoe => vec![Efficiency, SilkTouch, Fortune],
-            Axe => vec![
-                Sharpness,
-                Smite,
-                BaneOfArthropods,
-                Efficiency,
-                SilkTouch,
-                Fortune,
-            ],
-            Shears => vec![Efficiency],
-            FlintAndSteel | CarrotOnAStick | WarpedFungusOnAStick | Shield => Vec::new(),
-            FishingRod => vec![LuckOfTheSea, Lure],
-            Sword => vec![
-                Sharpness,
-                Smite,
-                BaneOfArthropods,
-                Knockback,
-                FireAspect,
-                Looting,
-                SweepingEdge,
-            ],
-            Bow => vec![Power, Punch, Flame, Infinity],
-            Crossbow => vec![Multishot, Piercing, QuickCharge],
-            Trident => vec![Impaling, Riptide, Loyalty, Channeling],
-            Mace => vec![
-                Density,
-                Breach,
-                WindBurst,
-                Smite,
-                BaneOfArthropods,
-                FireAspect,
-            ],
-            Helmet => vec![Respiration, AquaAffinity],
-            Chestplate => Vec::new(),
-            Leggings => vec![SwiftSneak],
-            Boots => vec![FeatherFalling, DepthStrider, SoulSpeed],
-            Elytra => vec![CurseOfBinding],
-        });
-
-        if self.item_type.is_armor() {
-            enchants.extend(vec![
-                Protection,
-                FireProtection,
-                BlastProtection,
-                ProjectileProtection,
-                Thorns,
-                CurseOfBinding,
-            ]);
-        }
-
-        enchants.into_iter()
+        Enchantment::iter()
+            .filter(move |enchantment| enchantment.is_applicable_to(&item_type, behavior))
     }
 }
 
@@ -264,6 +526,21 @@ impl Display for ItemType {
     }
 }
 
+impl Display for Item {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        use ItemType::*;
+
+        match (self.material, self.item_type) {
+            (None, item_type) => write!(f, "{item_type}"),
+            // leather armour has its own names
+            (Some(Material::Leather), Helmet) => write!(f, "Leather Cap"),
+            (Some(Material::Leather), Chestplate) => write!(f, "Leather Tunic"),
+            (Some(Material::Leather), Leggings) => write!(f, "Leather Pants"),
+            (Some(material), item_type) => write!(f, "{material} {item_type}"),
+        }
+    }
+}
+
 macro_rules! item {
     ($item_type: expr) => {{
         use crate::item::Item;
@@ -283,7 +560,11 @@ pub(crate) use item;
 
 #[cfg(test)]
 mod tests {
-    use crate::{enchantments::Enchantment, item::ItemType};
+    use crate::{
+        anvil::AnvilBehavior,
+        enchantments::Enchantment,
+        item::{ItemType, Material},
+    };
 
     #[test]
     fn enchant_item() {
@@ -299,5 +580,84 @@ mod tests {
         // the existing efficiency enchantment is overwritten.
         item.enchant(Enchantment::Efficiency, 1);
         assert_eq!(item.level_of(Enchantment::Efficiency), Some(1));
+
+        // commands can go past the maximum, up to 255
+        item.enchant_over_max(Enchantment::Efficiency, 10);
+        assert_eq!(item.level_of(Enchantment::Efficiency), Some(10));
+        item.enchant_over_max(Enchantment::Efficiency, 1000);
+        assert_eq!(item.level_of(Enchantment::Efficiency), Some(255));
+        assert_eq!(
+            item.split_into_books()[1].level_of(Enchantment::Efficiency),
+            Some(255)
+        );
+    }
+
+    #[test]
+    fn item_materials() {
+        let mut item = item!(ItemType::Pickaxe);
+        assert_eq!(item.material(), Some(Material::Diamond));
+        assert_eq!(item.max_durability(), Some(1561));
+        assert_eq!(item.to_string(), "Diamond Pickaxe");
+
+        item.set_material(Material::Netherite);
+        assert_eq!(item.max_durability(), Some(2031));
+        assert_eq!(item.repair_item(), Some("Netherite Ingot"));
+
+        // pickaxes can't be made out of leather
+        item.set_material(Material::Leather);
+        assert_eq!(item.material(), Some(Material::Netherite));
+
+        let mut helmet = item!(ItemType::Helmet);
+        helmet.set_material(Material::Gold);
+        assert_eq!(helmet.max_durability(), Some(77));
+        assert_eq!(helmet.enchantability(), Some(25));
+        assert_eq!(helmet.to_string(), "Golden Helmet");
+
+        let book = item!(ItemType::EnchantedBook);
+        assert_eq!(book.material(), None);
+        assert_eq!(book.to_string(), "Enchanted Book");
+    }
+
+    #[test]
+    fn max_and_split_enchantments() {
+        // silk touch is kept instead of fortune, and efficiency is raised to its maximum
+        let mut pickaxe = item!(
+            ItemType::Pickaxe,
+            (Enchantment::SilkTouch, 1),
+            (Enchantment::Efficiency, 2)
+        );
+        pickaxe.max_enchantments(&ItemType::Pickaxe, AnvilBehavior::Java);
+        assert_eq!(
+            pickaxe.enchantments(),
+            &vec![
+                (Enchantment::SilkTouch, 1),
+                (Enchantment::Efficiency, 5),
+                (Enchantment::Unbreaking, 3),
+                (Enchantment::Mending, 1),
+            ]
+        );
+
+        // books only get the enchantments for the item they're meant for
+        let mut book = item!(ItemType::EnchantedBook);
+        book.max_enchantments(&ItemType::Shears, AnvilBehavior::Java);
+        assert_eq!(book.enchantments().len(), 3);
+        assert_eq!(book.level_of(Enchantment::Efficiency), Some(5));
+
+        // levels over the max aren't lowered
+        let mut book = item!(ItemType::EnchantedBook);
+        book.enchant_over_max(Enchantment::Efficiency, 10);
+        book.max_enchantments(&ItemType::Shears, AnvilBehavior::Java);
+        assert_eq!(book.level_of(Enchantment::Efficiency), Some(10));
+        assert_eq!(book.level_of(Enchantment::Unbreaking), Some(3));
+
+        let books = pickaxe.split_into_books();
+        assert_eq!(books.len(), 4);
+        assert_eq!(
+            books[1],
+            item!(ItemType::EnchantedBook, (Enchantment::Efficiency, 5))
+        );
+
+        pickaxe.clear_enchantments();
+        assert!(pickaxe.enchantments().is_empty());
     }
 }
@@ -0,0 +1,322 @@
+//! a stable json representation of items and combination plans.
+//! items and enchantments are written using the game's namespaced ids, for example:
+//!
+//! ```json
+//! {
+//!   "version": 1,
+//!   "data": {
+//!     "id": "minecraft:diamond_sword",
+//!     "anvil_uses": 1,
+//!     "enchantments": { "minecraft:sharpness": 5, "minecraft:looting": 3 }
+//!   }
+//! }
+//! ```
+
+use std::fmt::{self, Display};
+
+use serde::{
+    de::{self, DeserializeOwned, MapAccess, Visitor},
+    ser::SerializeMap,
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+use serde_json::Value;
+
+use crate::{
+    enchantments::Enchantment,
+    item::{Item, ItemType, MAX_ANVIL_USES, OVER_MAX_LEVEL},
+};
+
+/// the version written by `to_json`. bump this (and migrate in `from_json`) whenever the format changes.
+pub const VERSION: u32 = 1;
+
+#[derive(Debug)]
+pub enum JsonError {
+    Syntax(serde_json::Error),
+    UnsupportedVersion(u32),
+}
+
+impl Display for JsonError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            JsonError::Syntax(error) => write!(f, "invalid json: {error}"),
+            JsonError::UnsupportedVersion(version) => {
+                write!(
+                    f,
+                    "unsupported format version {version}, expected at most {VERSION}"
+                )
+            }
+        }
+    }
+}
+
+impl From<serde_json::Error> for JsonError {
+    fn from(error: serde_json::Error) -> Self {
+        JsonError::Syntax(error)
+    }
+}
+
+#[derive(Serialize)]
+struct Versioned<'a, T> {
+    version: u32,
+    data: &'a T,
+}
+
+#[derive(Deserialize)]
+struct Unversioned {
+    version: u32,
+    data: Value,
+}
+
+/// serializes the value along with the current format version
+// nothing in the web app saves json yet
+#[cfg_attr(not(test), allow(dead_code))]
+pub fn to_json<T: Serialize>(value: &T) -> String {
+    serde_json::to_string(&Versioned {
+        version: VERSION,
+        data: value,
+    })
+    .expect("values with string keys always serialize")
+}
+
+/// deserializes a value written by `to_json`, rejecting versions newer than this build understands
+#[cfg_attr(not(test), allow(dead_code))]
+pub fn from_json<T: DeserializeOwned>(json: &str) -> Result<T, JsonError> {
+    let Unversioned { version, data } = serde_json::from_str(json)?;
+
+    match version {
+        1 => Ok(serde_json::from_value(data)?),
+        version => Err(JsonError::UnsupportedVersion(version)),
+    }
+}
+
+impl Serialize for Enchantment {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.id())
+    }
+}
+
+impl<'de> Deserialize<'de> for Enchantment {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let id = String::deserialize(deserializer)?;
+
+        Enchantment::from_id(&id)
+            .ok_or_else(|| de::Error::custom(format!("unknown enchantment `{id}`")))
+    }
+}
+
+impl Serialize for ItemType {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.id())
+    }
+}
+
+impl<'de> Deserialize<'de> for ItemType {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let id = String::deserialize(deserializer)?;
+
+        ItemType::from_id(&id).ok_or_else(|| de::Error::custom(format!("unknown item `{id}`")))
+    }
+}
+
+/// enchantments are written as an object from id to level, in the order they were applied
+struct Enchantments(Vec<(Enchantment, u32)>);
+
+impl Serialize for Enchantments {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(self.0.len()))?;
+        for (enchantment, level) in &self.0 {
+            map.serialize_entry(enchantment, level)?;
+        }
+        map.end()
+    }
+}
+
+impl<'de> Deserialize<'de> for Enchantments {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct EnchantmentsVisitor;
+
+        impl<'de> Visitor<'de> for EnchantmentsVisitor {
+            type Value = Enchantments;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("an object from enchantment id to level")
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+                let mut enchantments = Vec::new();
+                while let Some(entry) = map.next_entry()? {
+                    enchantments.push(entry);
+                }
+
+                Ok(Enchantments(enchantments))
+            }
+        }
+
+        deserializer.deserialize_map(EnchantmentsVisitor)
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct ItemData {
+    id: String,
+    #[serde(default)]
+    anvil_uses: u32,
+    #[serde(default = "no_enchantments")]
+    enchantments: Enchantments,
+}
+
+fn no_enchantments() -> Enchantments {
+    Enchantments(Vec::new())
+}
+
+impl Serialize for Item {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        ItemData {
+            id: self.id(),
+            anvil_uses: self.anvil_uses(),
+            enchantments: Enchantments(self.enchantments().clone()),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Item {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let data = ItemData::deserialize(deserializer)?;
+
+        let mut item = Item::from_id(&data.id)
+            .ok_or_else(|| de::Error::custom(format!("unknown item `{}`", data.id)))?;
+        if data.anvil_uses > MAX_ANVIL_USES {
+            return Err(de::Error::custom(format!(
+                "{} anvil uses is out of range",
+                data.anvil_uses
+            )));
+        }
+        item.set_anvil_uses(data.anvil_uses);
+        for (enchantment, level) in data.enchantments.0 {
+            if level == 0 || level > OVER_MAX_LEVEL {
+                return Err(de::Error::custom(format!(
+                    "level {level} is out of range for `{}`",
+                    enchantment.id()
+                )));
+            }
+
+            item.enchant_over_max(enchantment, level);
+        }
+
+        Ok(item)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        anvil::Anvil,
+        enchantments::Enchantment,
+        item::{item, Item, ItemType, Material},
+    };
+
+    use super::{from_json, to_json, JsonError};
+
+    #[test]
+    fn item_json() {
+        let mut sword = item!(
+            ItemType::Sword,
+            (Enchantment::Sharpness, 5),
+            (Enchantment::Looting, 3)
+        );
+        sword.set_material(Material::Netherite);
+        sword.set_anvil_uses(2);
+
+        let json = to_json(&sword);
+        assert_eq!(
+            json,
+            r#"{"version":1,"data":{"id":"minecraft:netherite_sword","anvil_uses":2,"enchantments":{"minecraft:sharpness":5,"minecraft:looting":3}}}"#
+        );
+        assert_eq!(from_json::<Item>(&json).unwrap(), sword);
+
+        // the namespace and optional fields can be left out
+        let book: Item = from_json(
+            r#"{"version":1,"data":{"id":"enchanted_book","enchantments":{"mending":1}}}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            book,
+            item!(ItemType::EnchantedBook, (Enchantment::Mending, 1))
+        );
+
+        assert_eq!(
+            to_json(&ItemType::MobHead),
+            r#"{"version":1,"data":"minecraft:player_head"}"#
+        );
+        assert_eq!(
+            from_json::<Vec<Enchantment>>(r#"{"version":1,"data":["minecraft:unbreaking"]}"#)
+                .unwrap(),
+            vec![Enchantment::Unbreaking]
+        );
+    }
+
+    #[test]
+    fn plan_json() {
+        let anvil = Anvil::new_java();
+        let results = anvil.combine_many(vec![
+            item!(ItemType::Pickaxe),
+            item!(ItemType::EnchantedBook, (Enchantment::Efficiency, 5)),
+            item!(ItemType::EnchantedBook, (Enchantment::Unbreaking, 3)),
+            item!(ItemType::EnchantedBook, (Enchantment::Mending, 1)),
+        ]);
+
+        let json = to_json(&results);
+        assert_eq!(
+            from_json::<crate::anvil::AnvilCombinationResults>(&json).unwrap(),
+            results
+        );
+
+        let steps = anvil.steps(results.lowest_solution);
+        assert_eq!(
+            from_json::<Vec<crate::anvil::AnvilStep>>(&to_json(&steps)).unwrap(),
+            steps
+        );
+    }
+
+    #[test]
+    fn json_errors() {
+        assert!(matches!(
+            from_json::<Item>(r#"{"version":2,"data":{"id":"minecraft:bow"}}"#),
+            Err(JsonError::UnsupportedVersion(2))
+        ));
+        assert!(matches!(
+            from_json::<Item>(r#"{"version":1,"data":{"id":"minecraft:stick"}}"#),
+            Err(JsonError::Syntax(_))
+        ));
+        assert!(matches!(
+            from_json::<Item>(
+                r#"{"version":1,"data":{"id":"minecraft:bow","enchantments":{"minecraft:power":256}}}"#
+            ),
+            Err(JsonError::Syntax(_))
+        ));
+        assert_eq!(
+            from_json::<Item>(
+                r#"{"version":1,"data":{"id":"minecraft:bow","enchantments":{"minecraft:power":9}}}"#
+            )
+            .unwrap()
+            .level_of(Enchantment::Power),
+            Some(9)
+        );
+        assert_eq!(
+            from_json::<Item>(r#"{"version":1,"data":{"id":"minecraft:bow","anvil_uses":6}}"#)
+                .unwrap()
+                .anvil_uses(),
+            6
+        );
+        assert!(matches!(
+            from_json::<Item>(r#"{"version":1,"data":{"id":"minecraft:bow","anvil_uses":7}}"#),
+            Err(JsonError::Syntax(_))
+        ));
+        assert!(matches!(
+            from_json::<Item>(r#"{"version":1,"data":{"id":"minecraft:bow","anvil_uses":31}}"#),
+            Err(JsonError::Syntax(_))
+        ));
+        assert!(from_json::<Item>(r#"{"data":{}}"#).is_err());
+    }
+}
@@ -0,0 +1,332 @@
+//! translations taken from the game's language files, like `assets/minecraft/lang/de_de.json`.
+//! only the keys the calculator uses are kept in `src/lang`, copied as they are from the game.
+//! text the game doesn't have, like the calculator's own buttons and notes, is under `calculator.`.
+//! keys a language is missing fall back to `en_us`, and then to the key itself, which is what the game does.
+//! to add a language, copy the same keys from its file into `src/lang` and add it to `Language`.
+
+use std::{collections::HashMap, iter::Peekable, str::Chars};
+
+use strum::{EnumIter, IntoEnumIterator};
+
+use crate::{enchantments::Enchantment, item::Item};
+
+#[derive(Clone, Copy, Debug, EnumIter, PartialEq)]
+pub enum Language {
+    EnglishUs,
+    German,
+}
+
+impl Language {
+    /// the name of the language file, without `.json`
+    pub fn id(&self) -> &'static str {
+        match self {
+            Self::EnglishUs => "en_us",
+            Self::German => "de_de",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Self> {
+        Self::iter().find(|language| language.id() == id)
+    }
+
+    fn file(&self) -> &'static str {
+        match self {
+            Self::EnglishUs => include_str!("lang/en_us.json"),
+            Self::German => include_str!("lang/de_de.json"),
+        }
+    }
+
+    pub fn translations(&self) -> Translations {
+        let mut keys = parse(Self::EnglishUs.file()).expect("en_us.json is valid");
+        keys.extend(parse(self.file()).expect("language files are valid"));
+
+        Translations { keys }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Translations {
+    keys: HashMap<String, String>,
+}
+
+impl Translations {
+    /// the text for the key, or the key itself if there isn't any
+    pub fn get(&self, key: &str) -> String {
+        self.keys
+            .get(key)
+            .cloned()
+            .unwrap_or_else(|| key.to_string())
+    }
+
+    /// the text for the key, with `%s` and `%1$s` style placeholders filled in
+    pub fn format(&self, key: &str, args: &[&str]) -> String {
+        let mut text = self.get(key);
+        for (i, arg) in args.iter().enumerate() {
+            text = text.replace(&format!("%{}$s", i + 1), arg);
+        }
+        for arg in args {
+            text = text.replacen("%s", arg, 1);
+        }
+
+        text
+    }
+
+    /// the language's own name, e.g. `Deutsch (Deutschland)`
+    pub fn name(&self) -> String {
+        format!(
+            "{} ({})",
+            self.get("language.name"),
+            self.get("language.region")
+        )
+    }
+
+    /// the item's name. some items, like heads, are named as blocks.
+    pub fn item(&self, item: &Item) -> String {
+        self.game_name(&item.id())
+            .unwrap_or_else(|| item.to_string())
+    }
+
+    /// the name of what the item is repaired with, if it can be repaired
+    pub fn repair_item(&self, item: &Item) -> Option<String> {
+        let name = item.repair_item()?;
+
+        // planks come in a kind per wood, so the game has no name for them all
+        if name == "Planks" {
+            return Some(self.get("calculator.item.planks"));
+        }
+
+        let id = format!("minecraft:{}", name.to_lowercase().replace(' ', "_"));
+        Some(self.game_name(&id).unwrap_or_else(|| name.to_string()))
+    }
+
+    /// the name of the item or block with the namespaced id
+    fn game_name(&self, id: &str) -> Option<String> {
+        let id = id.replace(':', ".");
+
+        ["item", "block"]
+            .iter()
+            .find_map(|kind| self.keys.get(&format!("{kind}.{id}")).cloned())
+    }
+
+    pub fn enchantment(&self, enchantment: Enchantment) -> String {
+        self.keys
+            .get(&format!(
+                "enchantment.{}",
+                enchantment.id().replace(':', ".")
+            ))
+            .cloned()
+            .unwrap_or_else(|| enchantment.to_string())
+    }
+
+    /// the enchantment's name with its level, following the same rules as `Enchantment::name_with_level`
+    pub fn enchantment_with_level(&self, enchantment: Enchantment, level: u32) -> String {
+        let name = self.enchantment(enchantment);
+
+        if level == 1 && enchantment.max_level() == 1 {
+            name
+        } else {
+            format!("{name} {}", self.get(&format!("enchantment.level.{level}")))
+        }
+    }
+}
+
+/// reads a language file, which is a json object from translation key to text.
+/// returns `None` if it isn't one.
+fn parse(json: &str) -> Option<HashMap<String, String>> {
+    let mut chars = json.chars().peekable();
+    let mut keys = HashMap::new();
+
+    skip_whitespace(&mut chars);
+    if chars.next()? != '{' {
+        return None;
+    }
+
+    loop {
+        skip_whitespace(&mut chars);
+        if chars.next_if_eq(&'}').is_some() && keys.is_empty() {
+            break;
+        }
+
+        let key = parse_string(&mut chars)?;
+        skip_whitespace(&mut chars);
+        if chars.next()? != ':' {
+            return None;
+        }
+        skip_whitespace(&mut chars);
+        let value = parse_string(&mut chars)?;
+        keys.insert(key, value);
+
+        skip_whitespace(&mut chars);
+        match chars.next()? {
+            ',' => {}
+            '}' => break,
+            _ => return None,
+        }
+    }
+
+    skip_whitespace(&mut chars);
+    chars.next().is_none().then_some(keys)
+}
+
+fn skip_whitespace(chars: &mut Peekable<Chars>) {
+    while chars.next_if(|c| c.is_whitespace()).is_some() {}
+}
+
+fn parse_string(chars: &mut Peekable<Chars>) -> Option<String> {
+    if chars.next()? != '"' {
+        return None;
+    }
+
+    let mut string = String::new();
+    loop {
+        match chars.next()? {
+            '"' => return Some(string),
+            '\\' => string.push(match chars.next()? {
+                'n' => '\n',
+                't' => '\t',
+                'r' => '\r',
+                'b' => '\u{8}',
+                'f' => '\u{c}',
+                'u' => {
+                    let mut code = parse_hex(chars)?;
+                    // characters outside the basic plane are written as two escapes
+                    if (0xd800..0xdc00).contains(&code) {
+                        if chars.next()? != '\\' || chars.next()? != 'u' {
+                            return None;
+                        }
+                        let low = parse_hex(chars)?;
+                        code = 0x10000 + ((code - 0xd800) << 10) + (low.checked_sub(0xdc00)?);
+                    }
+
+                    char::from_u32(code)?
+                }
+                c => c,
+            }),
+            c => string.push(c),
+        }
+    }
+}
+
+fn parse_hex(chars: &mut Peekable<Chars>) -> Option<u32> {
+    let hex: String = chars.take(4).collect();
+    if hex.len() != 4 {
+        return None;
+    }
+
+    u32::from_str_radix(&hex, 16).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use strum::IntoEnumIterator;
+
+    use crate::{
+        enchantments::Enchantment,
+        item::{item, ItemType, Material},
+    };
+
+    use super::{parse, Language};
+
+    #[test]
+    fn language_files() {
+        let english = Language::EnglishUs.translations();
+        for language in Language::iter() {
+            let translations = language.translations();
+            assert_eq!(Language::from_id(language.id()), Some(language));
+
+            // every item and enchantment has a name in every language
+            for item_type in ItemType::iter() {
+                let mut items = vec![item!(item_type)];
+                for material in item_type.materials() {
+                    let mut item = item!(item_type);
+                    item.set_material(*material);
+                    items.push(item);
+                }
+
+                for item in items {
+                    let id = item.id().replace(':', ".");
+                    assert!(
+                        [format!("item.{id}"), format!("block.{id}")]
+                            .iter()
+                            .any(|key| translations.keys.contains_key(key)),
+                        "{} has no name for {id}",
+                        language.id()
+                    );
+
+                    if let Some(name) = item.repair_item().filter(|name| *name != "Planks") {
+                        let id = format!("minecraft:{}", name.to_lowercase().replace(' ', "_"));
+                        assert!(
+                            translations.game_name(&id).is_some(),
+                            "{} has no name for {id}",
+                            language.id()
+                        );
+                    }
+                }
+            }
+            for enchantment in Enchantment::iter() {
+                let id = enchantment.id().replace(':', ".");
+                assert!(
+                    translations.keys.contains_key(&format!("enchantment.{id}")),
+                    "{} has no name for {id}",
+                    language.id()
+                );
+            }
+
+            // nothing is left over from english
+            assert_eq!(translations.keys.len(), english.keys.len());
+        }
+    }
+
+    #[test]
+    fn translate_names() {
+        let german = Language::German.translations();
+
+        let mut helmet = item!(ItemType::Helmet);
+        helmet.set_material(Material::Leather);
+        assert_eq!(german.item(&helmet), "Lederkappe");
+        assert_eq!(german.item(&item!(ItemType::MobHead)), "Spielerkopf");
+        assert_eq!(german.name(), "Deutsch (Deutschland)");
+
+        assert_eq!(
+            german.enchantment_with_level(Enchantment::Sharpness, 5),
+            "Schärfe V"
+        );
+        assert_eq!(
+            german.enchantment_with_level(Enchantment::Mending, 1),
+            "Reparatur"
+        );
+        assert_eq!(
+            german.enchantment_with_level(Enchantment::Sharpness, 11),
+            "Schärfe enchantment.level.11"
+        );
+        assert_eq!(
+            german.format("container.repair.cost", &["17"]),
+            "Erfahrungskosten: 17"
+        );
+
+        let mut pickaxe = item!(ItemType::Pickaxe);
+        pickaxe.set_material(Material::Stone);
+        assert_eq!(german.repair_item(&pickaxe).unwrap(), "Bruchstein");
+        assert_eq!(
+            german.repair_item(&item!(ItemType::Shield)).unwrap(),
+            "Bretter"
+        );
+        assert_eq!(german.repair_item(&item!(ItemType::Bow)), None);
+
+        let english = Language::EnglishUs.translations();
+        assert_eq!(english.item(&helmet), "Leather Cap");
+        assert_eq!(english.get("unknown.key"), "unknown.key");
+    }
+
+    #[test]
+    fn parse_language_file() {
+        let keys = parse(r#" { "a": "b", "escaped": "\"\\\né😀" } "#).unwrap();
+        assert_eq!(keys["a"], "b");
+        assert_eq!(keys["escaped"], "\"\\\né😀");
+
+        assert!(parse("{}").unwrap().is_empty());
+        assert!(parse(r#"{"a": 1}"#).is_none());
+        assert!(parse(r#"{"a": "b",}"#).is_none());
+        assert!(parse(r#"{"a": "b"} x"#).is_none());
+    }
+}
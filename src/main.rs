@@ -2,9 +2,17 @@ use web::App;
 
 mod anvil;
 mod enchantments;
+mod export;
+mod import;
 mod item;
+#[cfg(feature = "serde")]
+mod json;
+mod lang;
+mod nbt;
+mod permalink;
 mod presets;
 mod util;
+mod validate;
 mod web;
 
 fn main() {
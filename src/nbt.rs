@@ -0,0 +1,65 @@
+pub mod binary;
+pub mod snbt;
+
+/// a named binary tag, the format the game stores items in.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Tag {
+    Byte(i8),
+    Short(i16),
+    Int(i32),
+    Long(i64),
+    Float(f32),
+    Double(f64),
+    ByteArray(Vec<i8>),
+    String(String),
+    List(Vec<Tag>),
+    /// the entries of a compound, in the order they were read
+    Compound(Vec<(String, Tag)>),
+    IntArray(Vec<i32>),
+    LongArray(Vec<i64>),
+}
+
+impl Tag {
+    /// gets the value with the given key, if this is a compound tag.
+    /// keys are looked up both with and without the `minecraft:` namespace.
+    pub fn get(&self, key: &str) -> Option<&Tag> {
+        let key = key.strip_prefix("minecraft:").unwrap_or(key);
+
+        self.as_compound()?
+            .iter()
+            .find(|(name, _)| name.strip_prefix("minecraft:").unwrap_or(name) == key)
+            .map(|(_, value)| value)
+    }
+
+    pub fn as_compound(&self) -> Option<&Vec<(String, Tag)>> {
+        match self {
+            Self::Compound(compound) => Some(compound),
+            _ => None,
+        }
+    }
+
+    pub fn as_list(&self) -> Option<&Vec<Tag>> {
+        match self {
+            Self::List(list) => Some(list),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Self::String(string) => Some(string),
+            _ => None,
+        }
+    }
+
+    /// returns the value of any integer tag
+    pub fn as_int(&self) -> Option<i64> {
+        match self {
+            Self::Byte(n) => Some(*n as i64),
+            Self::Short(n) => Some(*n as i64),
+            Self::Int(n) => Some(*n as i64),
+            Self::Long(n) => Some(*n),
+            _ => None,
+        }
+    }
+}
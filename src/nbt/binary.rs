@@ -0,0 +1,188 @@
+//! a reader for the binary nbt format used by world saves, such as `level.dat` and `playerdata/<uuid>.dat`.
+
+use std::{fmt::Display, io::Read};
+
+use flate2::read::GzDecoder;
+
+use super::Tag;
+
+#[derive(Debug, PartialEq)]
+pub enum NbtError {
+    /// the gzip compression is invalid
+    Compression(String),
+    /// the data ended in the middle of a tag
+    UnexpectedEnd,
+    /// a tag has an id that doesn't exist
+    InvalidTagId(u8),
+    /// the root tag isn't a compound
+    InvalidRoot,
+}
+
+impl Display for NbtError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Compression(error) => write!(f, "invalid compression: {error}"),
+            Self::UnexpectedEnd => write!(f, "the file ended unexpectedly"),
+            Self::InvalidTagId(id) => write!(f, "invalid tag id {id}"),
+            Self::InvalidRoot => write!(f, "the file doesn't start with a compound"),
+        }
+    }
+}
+
+/// reads the root compound of an nbt file. gzip compressed files are decompressed first.
+pub fn read(bytes: &[u8]) -> Result<Tag, NbtError> {
+    let mut decompressed = Vec::new();
+    let bytes = if bytes.starts_with(&[0x1f, 0x8b]) {
+        GzDecoder::new(bytes)
+            .read_to_end(&mut decompressed)
+            .map_err(|error| NbtError::Compression(error.to_string()))?;
+        &decompressed[..]
+    } else {
+        bytes
+    };
+
+    let mut reader = Reader { bytes, position: 0 };
+    if reader.read_u8()? != 10 {
+        return Err(NbtError::InvalidRoot);
+    }
+
+    // the root compound has a name, which is usually empty
+    reader.read_string()?;
+    reader.read_payload(10)
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, length: usize) -> Result<&'a [u8], NbtError> {
+        let end = self.position + length;
+        let bytes = self
+            .bytes
+            .get(self.position..end)
+            .ok_or(NbtError::UnexpectedEnd)?;
+
+        self.position = end;
+        Ok(bytes)
+    }
+
+    fn read_array<const N: usize>(&mut self) -> Result<[u8; N], NbtError> {
+        Ok(self.take(N)?.try_into().unwrap())
+    }
+
+    fn read_u8(&mut self) -> Result<u8, NbtError> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn read_length(&mut self) -> Result<usize, NbtError> {
+        // negative lengths mean the list or array is empty
+        Ok(i32::from_be_bytes(self.read_array()?).max(0) as usize)
+    }
+
+    fn read_string(&mut self) -> Result<String, NbtError> {
+        let length = u16::from_be_bytes(self.read_array()?) as usize;
+
+        // java uses modified utf-8, which only differs for null and characters outside the bmp
+        Ok(String::from_utf8_lossy(self.take(length)?).into_owned())
+    }
+
+    fn read_payload(&mut self, id: u8) -> Result<Tag, NbtError> {
+        Ok(match id {
+            1 => Tag::Byte(i8::from_be_bytes(self.read_array()?)),
+            2 => Tag::Short(i16::from_be_bytes(self.read_array()?)),
+            3 => Tag::Int(i32::from_be_bytes(self.read_array()?)),
+            4 => Tag::Long(i64::from_be_bytes(self.read_array()?)),
+            5 => Tag::Float(f32::from_be_bytes(self.read_array()?)),
+            6 => Tag::Double(f64::from_be_bytes(self.read_array()?)),
+            7 => {
+                let length = self.read_length()?;
+                Tag::ByteArray(self.take(length)?.iter().map(|b| *b as i8).collect())
+            }
+            8 => Tag::String(self.read_string()?),
+            9 => {
+                let element_id = self.read_u8()?;
+                let length = self.read_length()?;
+
+                let mut list = Vec::new();
+                for _ in 0..length {
+                    list.push(self.read_payload(element_id)?);
+                }
+                Tag::List(list)
+            }
+            10 => {
+                let mut compound = Vec::new();
+                loop {
+                    let id = self.read_u8()?;
+                    if id == 0 {
+                        break;
+                    }
+
+                    let name = self.read_string()?;
+                    compound.push((name, self.read_payload(id)?));
+                }
+                Tag::Compound(compound)
+            }
+            11 => {
+                let length = self.read_length()?;
+                let mut array = Vec::new();
+                for _ in 0..length {
+                    array.push(i32::from_be_bytes(self.read_array()?));
+                }
+                Tag::IntArray(array)
+            }
+            12 => {
+                let length = self.read_length()?;
+                let mut array = Vec::new();
+                for _ in 0..length {
+                    array.push(i64::from_be_bytes(self.read_array()?));
+                }
+                Tag::LongArray(array)
+            }
+            id => return Err(NbtError::InvalidTagId(id)),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use flate2::{write::GzEncoder, Compression};
+
+    use super::{read, NbtError};
+    use crate::nbt::Tag;
+
+    /// `{name: "anvil", uses: [3s, 7s]}`, as an uncompressed nbt file
+    const FILE: &[u8] = &[
+        10, 0, 0, // root compound with an empty name
+        8, 0, 4, b'n', b'a', b'm', b'e', 0, 5, b'a', b'n', b'v', b'i', b'l', // name
+        9, 0, 4, b'u', b's', b'e', b's', 2, 0, 0, 0, 2, 0, 3, 0, 7, // uses
+        0, // end of root
+    ];
+
+    #[test]
+    fn read_file() {
+        let expected = Tag::Compound(vec![
+            ("name".to_string(), Tag::String("anvil".to_string())),
+            (
+                "uses".to_string(),
+                Tag::List(vec![Tag::Short(3), Tag::Short(7)]),
+            ),
+        ]);
+
+        assert_eq!(read(FILE), Ok(expected.clone()));
+
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(FILE).unwrap();
+        assert_eq!(read(&encoder.finish().unwrap()), Ok(expected));
+    }
+
+    #[test]
+    fn read_errors() {
+        assert_eq!(read(&FILE[..FILE.len() - 1]), Err(NbtError::UnexpectedEnd));
+        assert_eq!(read(&[8, 0, 0, 0, 0]), Err(NbtError::InvalidRoot));
+        assert_eq!(read(&[10, 0, 0, 13, 0, 0]), Err(NbtError::InvalidTagId(13)));
+    }
+}
@@ -0,0 +1,336 @@
+//! a parser for stringified nbt, the text format used in commands (e.g. `{Enchantments:[{id:"sharpness",lvl:5s}]}`).
+
+use std::fmt::Display;
+
+use super::Tag;
+
+#[derive(Debug, PartialEq)]
+pub struct SnbtError {
+    /// the byte offset into the input where parsing failed
+    pub position: usize,
+    /// a description of what was expected at that position
+    pub expected: &'static str,
+}
+
+impl Display for SnbtError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "expected {} at position {}",
+            self.expected, self.position
+        )
+    }
+}
+
+/// parses a complete snbt value. only trailing whitespace may follow the value.
+/// ```
+/// assert_eq!(parse("{lvl:5s}")?.get("lvl"), Some(&Tag::Short(5)));
+/// ```
+pub fn parse(input: &str) -> Result<Tag, SnbtError> {
+    let mut reader = Reader::new(input);
+    let tag = reader.read_value()?;
+
+    reader.skip_whitespace();
+    if !reader.is_done() {
+        return Err(reader.error("end of input"));
+    }
+
+    Ok(tag)
+}
+
+/// reads snbt values and other command syntax from a string.
+pub struct Reader<'a> {
+    input: &'a str,
+    position: usize,
+}
+
+impl<'a> Reader<'a> {
+    pub fn new(input: &'a str) -> Self {
+        Self { input, position: 0 }
+    }
+
+    pub fn error(&self, expected: &'static str) -> SnbtError {
+        SnbtError {
+            position: self.position,
+            expected,
+        }
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.position >= self.input.len()
+    }
+
+    pub fn peek(&self) -> Option<char> {
+        self.input[self.position..].chars().next()
+    }
+
+    pub fn skip_whitespace(&mut self) {
+        self.read_while(char::is_whitespace);
+    }
+
+    /// consumes the given character (after any whitespace) if it is next.
+    /// returns `true` if it was consumed.
+    pub fn eat(&mut self, c: char) -> bool {
+        self.skip_whitespace();
+
+        if self.peek() == Some(c) {
+            self.position += c.len_utf8();
+            true
+        } else {
+            false
+        }
+    }
+
+    /// consumes the given character, or fails if it isn't next
+    pub fn expect(&mut self, c: char, expected: &'static str) -> Result<(), SnbtError> {
+        if self.eat(c) {
+            Ok(())
+        } else {
+            Err(self.error(expected))
+        }
+    }
+
+    /// consumes characters as long as they match the predicate
+    pub fn read_while(&mut self, mut predicate: impl FnMut(char) -> bool) -> &'a str {
+        let start = self.position;
+
+        while let Some(c) = self.peek() {
+            if !predicate(c) {
+                break;
+            }
+            self.position += c.len_utf8();
+        }
+
+        &self.input[start..self.position]
+    }
+
+    /// reads a resource location, such as `minecraft:diamond_sword`
+    pub fn read_resource_location(&mut self) -> Result<&'a str, SnbtError> {
+        self.skip_whitespace();
+        let location = self
+            .read_while(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.' | ':' | '/'));
+
+        if location.is_empty() {
+            Err(self.error("resource location"))
+        } else {
+            Ok(location)
+        }
+    }
+
+    /// reads a string, which may or may not be quoted
+    pub fn read_string(&mut self) -> Result<String, SnbtError> {
+        self.skip_whitespace();
+
+        match self.peek() {
+            Some(quote @ ('"' | '\'')) => self.read_quoted(quote),
+            _ => {
+                let string = self.read_unquoted();
+                if string.is_empty() {
+                    Err(self.error("string"))
+                } else {
+                    Ok(string.to_string())
+                }
+            }
+        }
+    }
+
+    fn read_unquoted(&mut self) -> &'a str {
+        self.read_while(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.' | '+'))
+    }
+
+    fn read_quoted(&mut self, quote: char) -> Result<String, SnbtError> {
+        // skip the opening quote
+        self.position += 1;
+
+        let mut string = String::new();
+        let mut escaped = false;
+
+        while let Some(c) = self.peek() {
+            self.position += c.len_utf8();
+
+            if escaped {
+                string.push(match c {
+                    'n' => '\n',
+                    't' => '\t',
+                    _ => c,
+                });
+                escaped = false;
+            } else if c == '\\' {
+                escaped = true;
+            } else if c == quote {
+                return Ok(string);
+            } else {
+                string.push(c);
+            }
+        }
+
+        Err(self.error("closing quote"))
+    }
+
+    /// reads any snbt value
+    pub fn read_value(&mut self) -> Result<Tag, SnbtError> {
+        self.skip_whitespace();
+
+        match self.peek() {
+            Some('{') => self.read_compound(),
+            Some('[') => self.read_list(),
+            Some('"' | '\'') => self.read_string().map(Tag::String),
+            _ => {
+                let token = self.read_unquoted();
+                if token.is_empty() {
+                    Err(self.error("value"))
+                } else {
+                    Ok(parse_unquoted(token))
+                }
+            }
+        }
+    }
+
+    fn read_compound(&mut self) -> Result<Tag, SnbtError> {
+        self.expect('{', "'{'")?;
+        let mut compound = Vec::new();
+
+        if !self.eat('}') {
+            loop {
+                let key = self.read_string()?;
+                self.expect(':', "':'")?;
+                compound.push((key, self.read_value()?));
+
+                if self.eat('}') {
+                    break;
+                }
+                self.expect(',', "',' or '}'")?;
+            }
+        }
+
+        Ok(Tag::Compound(compound))
+    }
+
+    fn read_list(&mut self) -> Result<Tag, SnbtError> {
+        self.expect('[', "'['")?;
+
+        // typed arrays look like `[I;1,2,3]`
+        let rest = &self.input[self.position..];
+        if let Some(array_type @ ('B' | 'I' | 'L')) = rest.chars().next() {
+            if rest[1..].trim_start().starts_with(';') {
+                self.position += 1;
+                self.expect(';', "';'")?;
+                return self.read_array(array_type);
+            }
+        }
+
+        let mut list = Vec::new();
+        if !self.eat(']') {
+            loop {
+                list.push(self.read_value()?);
+
+                if self.eat(']') {
+                    break;
+                }
+                self.expect(',', "',' or ']'")?;
+            }
+        }
+
+        Ok(Tag::List(list))
+    }
+
+    fn read_array(&mut self, array_type: char) -> Result<Tag, SnbtError> {
+        let mut values = Vec::new();
+
+        if !self.eat(']') {
+            loop {
+                let value = self.read_value()?;
+                values.push(value.as_int().ok_or(self.error("integer"))?);
+
+                if self.eat(']') {
+                    break;
+                }
+                self.expect(',', "',' or ']'")?;
+            }
+        }
+
+        Ok(match array_type {
+            'B' => Tag::ByteArray(values.into_iter().map(|n| n as i8).collect()),
+            'I' => Tag::IntArray(values.into_iter().map(|n| n as i32).collect()),
+            _ => Tag::LongArray(values),
+        })
+    }
+}
+
+/// turns an unquoted token into a number, boolean or string
+fn parse_unquoted(token: &str) -> Tag {
+    match token {
+        "true" => return Tag::Byte(1),
+        "false" => return Tag::Byte(0),
+        _ => {}
+    }
+
+    let (number, suffix) = token.split_at(token.len() - 1);
+    let typed = match suffix {
+        "b" | "B" => number.parse().ok().map(Tag::Byte),
+        "s" | "S" => number.parse().ok().map(Tag::Short),
+        "l" | "L" => number.parse().ok().map(Tag::Long),
+        "f" | "F" => number.parse().ok().map(Tag::Float),
+        "d" | "D" => number.parse().ok().map(Tag::Double),
+        _ => None,
+    };
+
+    typed
+        .or_else(|| token.parse().ok().map(Tag::Int))
+        .or_else(|| {
+            // doubles need a decimal point or exponent, otherwise words like `inf` would count
+            let is_decimal = token.contains(['.', 'e', 'E'])
+                && token.starts_with(|c: char| c.is_ascii_digit() || matches!(c, '-' | '+' | '.'));
+
+            is_decimal
+                .then(|| token.parse().ok())
+                .flatten()
+                .map(Tag::Double)
+        })
+        .unwrap_or_else(|| Tag::String(token.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse, Tag};
+
+    #[test]
+    fn parse_values() {
+        assert_eq!(parse("5"), Ok(Tag::Int(5)));
+        assert_eq!(parse("5s"), Ok(Tag::Short(5)));
+        assert_eq!(parse("-3b"), Ok(Tag::Byte(-3)));
+        assert_eq!(parse("1.5"), Ok(Tag::Double(1.5)));
+        assert_eq!(parse("1.5f"), Ok(Tag::Float(1.5)));
+        assert_eq!(parse("true"), Ok(Tag::Byte(1)));
+        assert_eq!(parse("sharpness"), Ok(Tag::String("sharpness".into())));
+        assert_eq!(
+            parse(r#""minecraft:sharp\"ness""#),
+            Ok(Tag::String("minecraft:sharp\"ness".into()))
+        );
+        assert_eq!(parse("[I; 1, 2]"), Ok(Tag::IntArray(vec![1, 2])));
+    }
+
+    #[test]
+    fn parse_nested() {
+        let tag =
+            parse(r#"{Enchantments:[{id:"minecraft:sharpness",lvl:5s}], RepairCost: 3}"#).unwrap();
+
+        assert_eq!(tag.get("RepairCost"), Some(&Tag::Int(3)));
+        assert_eq!(
+            tag.get("Enchantments"),
+            Some(&Tag::List(vec![Tag::Compound(vec![
+                ("id".to_string(), Tag::String("minecraft:sharpness".into())),
+                ("lvl".to_string(), Tag::Short(5)),
+            ])]))
+        );
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert!(parse("{lvl:5").is_err());
+        assert!(parse("{lvl 5}").is_err());
+        assert!(parse("[1,2").is_err());
+        assert!(parse("'unterminated").is_err());
+        assert!(parse("5 6").is_err());
+    }
+}
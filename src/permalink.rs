@@ -0,0 +1,280 @@
+//! encodes the inventory into a short string for sharing in the url fragment, e.g.
+//! `1;j;diamond_pickaxe.2:efficiency5,unbreaking3;enchanted_book:mending1`.
+//!
+//! the first field is the format version and the second is the edition (`j` or `b`),
+//! followed by `+` if levels are allowed over the max.
+//! every other field is an item: its id, then `.` and its anvil uses if it has been used,
+//! then `:` and its enchantments as ids followed by levels.
+//! the item pinned as the target, if any, starts with `!`.
+//! game ids are used instead of indices so links keep working when enchantments or items are added.
+//! if the encoding has to change, bump `VERSION` and keep decoding the older versions, since the
+//! encoding is also used to save the inventory in local storage.
+
+use std::fmt::Display;
+
+use crate::{
+    anvil::AnvilBehavior,
+    enchantments::Enchantment,
+    item::{Item, MAX_ANVIL_USES, OVER_MAX_LEVEL},
+};
+
+/// the version written by `encode`
+const VERSION: &str = "1";
+
+/// everything a permalink restores
+#[derive(Debug, PartialEq)]
+pub struct Setup {
+    pub behavior: AnvilBehavior,
+    pub items: Vec<Item>,
+    /// the index of the item pinned as the target
+    pub pinned: Option<usize>,
+    /// whether levels are allowed over the max, up to `OVER_MAX_LEVEL`
+    pub over_max: bool,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum PermalinkError {
+    /// the link was made by a newer version of the calculator
+    UnsupportedVersion(String),
+    /// the item id isn't one the calculator knows about
+    UnknownItem(String),
+    /// the enchantment id isn't one the calculator knows about
+    UnknownEnchantment(String),
+    /// a part of the link couldn't be read
+    Malformed(&'static str),
+}
+
+impl Display for PermalinkError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::UnsupportedVersion(version) => {
+                write!(f, "this link uses an unsupported format ({version})")
+            }
+            Self::UnknownItem(id) => write!(f, "unknown item in link: {id}"),
+            Self::UnknownEnchantment(id) => write!(f, "unknown enchantment in link: {id}"),
+            Self::Malformed(part) => write!(f, "invalid {part} in link"),
+        }
+    }
+}
+
+/// encodes the setup into a permalink fragment, without the leading `#`
+pub fn encode(setup: &Setup) -> String {
+    let mut edition = match setup.behavior {
+        AnvilBehavior::Java => "j",
+        AnvilBehavior::Bedrock => "b",
+    }
+    .to_string();
+    if setup.over_max {
+        edition.push('+');
+    }
+
+    let mut fields = vec![VERSION.to_string(), edition];
+    for (i, item) in setup.items.iter().enumerate() {
+        let mut field = item.id().replace("minecraft:", "");
+        if setup.pinned == Some(i) {
+            field.insert(0, '!');
+        }
+
+        if item.anvil_uses() > 0 {
+            field += &format!(".{}", item.anvil_uses());
+        }
+
+        if !item.enchantments().is_empty() {
+            field.push(':');
+            field += &item
+                .enchantments()
+                .iter()
+                .map(|(enchantment, level)| {
+                    format!("{}{level}", enchantment.id().replace("minecraft:", ""))
+                })
+                .collect::<Vec<_>>()
+                .join(",");
+        }
+
+        fields.push(field);
+    }
+
+    fields.join(";")
+}
+
+/// decodes a permalink fragment made by `encode`. a leading `#` is ignored.
+pub fn decode(fragment: &str) -> Result<Setup, PermalinkError> {
+    let fragment = fragment.strip_prefix('#').unwrap_or(fragment);
+    let mut fields = fragment.split(';');
+
+    match fields.next() {
+        Some(VERSION) => {}
+        version => {
+            return Err(PermalinkError::UnsupportedVersion(
+                version.unwrap_or_default().to_string(),
+            ))
+        }
+    }
+
+    let edition = fields.next().unwrap_or_default();
+    let (edition, over_max) = match edition.strip_suffix('+') {
+        Some(edition) => (edition, true),
+        None => (edition, false),
+    };
+    let behavior = match edition {
+        "j" => AnvilBehavior::Java,
+        "b" => AnvilBehavior::Bedrock,
+        _ => return Err(PermalinkError::Malformed("edition")),
+    };
+
+    let mut pinned = None;
+    let mut items = Vec::new();
+    for (i, field) in fields.enumerate() {
+        let field = match field.strip_prefix('!') {
+            Some(field) => {
+                pinned = Some(i);
+                field
+            }
+            None => field,
+        };
+
+        items.push(decode_item(field)?);
+    }
+
+    Ok(Setup {
+        behavior,
+        items,
+        pinned,
+        over_max,
+    })
+}
+
+fn decode_item(field: &str) -> Result<Item, PermalinkError> {
+    let (item, enchantments) = field.split_once(':').unwrap_or((field, ""));
+    let (id, anvil_uses) = item.split_once('.').unwrap_or((item, "0"));
+
+    let mut item = Item::from_id(id).ok_or_else(|| PermalinkError::UnknownItem(id.to_string()))?;
+    item.set_anvil_uses(
+        anvil_uses
+            .parse()
+            .ok()
+            .filter(|uses| *uses <= MAX_ANVIL_USES)
+            .ok_or(PermalinkError::Malformed("work penalty"))?,
+    );
+
+    for enchantment in enchantments.split(',').filter(|e| !e.is_empty()) {
+        let digits = enchantment.trim_end_matches(|c: char| c.is_ascii_digit());
+        let (id, level) = enchantment.split_at(digits.len());
+
+        let enchantment = Enchantment::from_id(id)
+            .ok_or_else(|| PermalinkError::UnknownEnchantment(id.to_string()))?;
+        let level = level
+            .parse()
+            .ok()
+            .filter(|level| (1..=OVER_MAX_LEVEL).contains(level))
+            .ok_or(PermalinkError::Malformed("enchantment level"))?;
+
+        // levels past the maximum are kept, in case they were made with commands
+        item.enchant_over_max(enchantment, level);
+    }
+
+    Ok(item)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        anvil::AnvilBehavior,
+        enchantments::Enchantment,
+        item::{item, ItemType, Material},
+    };
+
+    use super::{decode, encode, PermalinkError, Setup};
+
+    #[test]
+    fn permalink_round_trip() {
+        let mut pickaxe = item!(
+            ItemType::Pickaxe,
+            (Enchantment::Efficiency, 5),
+            (Enchantment::Unbreaking, 3)
+        );
+        pickaxe.set_material(Material::Netherite);
+        pickaxe.set_anvil_uses(2);
+        let items = vec![
+            pickaxe,
+            item!(ItemType::EnchantedBook, (Enchantment::Mending, 1)),
+            item!(ItemType::EnchantedBook, (Enchantment::Fortune, 3)),
+        ];
+
+        let setup = Setup {
+            behavior: AnvilBehavior::Bedrock,
+            items,
+            pinned: Some(0),
+            over_max: false,
+        };
+
+        let link = encode(&setup);
+        assert_eq!(
+            link,
+            "1;b;!netherite_pickaxe.2:efficiency5,unbreaking3;enchanted_book:mending1;enchanted_book:fortune3"
+        );
+        assert_eq!(decode(&format!("#{link}")), Ok(setup));
+
+        assert_eq!(
+            decode("1;j"),
+            Ok(Setup {
+                behavior: AnvilBehavior::Java,
+                items: vec![],
+                pinned: None,
+                over_max: false,
+            })
+        );
+        assert_eq!(
+            decode("1;j;player_head").map(|setup| setup.items),
+            Ok(vec![item!(ItemType::MobHead)])
+        );
+
+        let setup = decode("1;j+;enchanted_book:sharpness10").unwrap();
+        assert!(setup.over_max);
+        assert_eq!(setup.items[0].level_of(Enchantment::Sharpness), Some(10));
+        assert_eq!(encode(&setup), "1;j+;enchanted_book:sharpness10");
+        assert!(decode("1;b+").unwrap().over_max);
+    }
+
+    #[test]
+    fn permalink_errors() {
+        assert_eq!(
+            decode("2;j;bow"),
+            Err(PermalinkError::UnsupportedVersion("2".to_string()))
+        );
+        assert_eq!(decode("1;x;bow"), Err(PermalinkError::Malformed("edition")));
+        assert_eq!(
+            decode("1;j++;bow"),
+            Err(PermalinkError::Malformed("edition"))
+        );
+        assert_eq!(
+            decode("1;j;stick"),
+            Err(PermalinkError::UnknownItem("stick".to_string()))
+        );
+        assert_eq!(
+            decode("1;j;bow:power5,aim3"),
+            Err(PermalinkError::UnknownEnchantment("aim".to_string()))
+        );
+        assert_eq!(
+            decode("1;j;bow:power"),
+            Err(PermalinkError::Malformed("enchantment level"))
+        );
+        assert_eq!(
+            decode("1;j;bow:power256"),
+            Err(PermalinkError::Malformed("enchantment level"))
+        );
+        assert_eq!(
+            decode("1;j;bow.x"),
+            Err(PermalinkError::Malformed("work penalty"))
+        );
+        assert!(decode("1;j;bow.6").is_ok());
+        assert_eq!(
+            decode("1;j;bow.7"),
+            Err(PermalinkError::Malformed("work penalty"))
+        );
+        assert_eq!(
+            decode("1;j;bow.31"),
+            Err(PermalinkError::Malformed("work penalty"))
+        );
+    }
+}
@@ -30,6 +30,15 @@ pub fn presets() -> Vec<Preset> {
             AquaAffinity,
             Thorns
         ),
+        preset!(
+            TurtleHelmet,
+            Unbreaking,
+            Mending,
+            Protection,
+            Respiration,
+            AquaAffinity,
+            Thorns
+        ),
         preset!(Chestplate, Unbreaking, Mending, BlastProtection, Thorns),
         preset!(Leggings, Unbreaking, Mending, Protection, Thorns, SwiftSneak),
         preset!(
@@ -44,11 +53,13 @@ pub fn presets() -> Vec<Preset> {
         ),
         preset!(Shield, Unbreaking, Mending),
         preset!(Elytra, Unbreaking, Mending),
+        preset!(WolfArmor, Unbreaking, Mending),
         preset!(FishingRod, Unbreaking, Mending, LuckOfTheSea, Lure),
         preset!(Shears, Unbreaking, Mending, Efficiency),
         preset!(FlintAndSteel, Unbreaking, Mending),
         preset!(CarrotOnAStick, Unbreaking, Mending),
         preset!(WarpedFungusOnAStick, Unbreaking, Mending),
+        preset!(Brush, Unbreaking, Mending),
     ]
 }
 
@@ -1,22 +1,17 @@
-/// converts an integer to a roman numeral with one space prior.
-/// the number 1 will return an empty string.
+/// formats an enchantment level the way the game does.
+/// the game only has names for levels 1 to 10, so higher levels show the untranslated key.
 /// ```
-/// assert_eq!(to_roman_numerals(1), String::from(""));
-/// assert_eq!(to_roman_numerals(2), String::from(" II"));
-/// assert_eq!(to_roman_numerals(3), String::from(" III"));
+/// assert_eq!(to_roman_numerals(1), String::from("I"));
+/// assert_eq!(to_roman_numerals(9), String::from("IX"));
+/// assert_eq!(to_roman_numerals(11), String::from("enchantment.level.11"));
 /// ```
 pub fn to_roman_numerals(n: u32) -> String {
-    let default = format!(" {n}");
+    const NUMERALS: [&str; 10] = ["I", "II", "III", "IV", "V", "VI", "VII", "VIII", "IX", "X"];
 
     match n {
-        1 => "",
-        2 => " II",
-        3 => " III",
-        4 => " IV",
-        5 => " V",
-        _ => &default,
+        1..=10 => NUMERALS[n as usize - 1].to_string(),
+        _ => format!("enchantment.level.{n}"),
     }
-    .to_string()
 }
 
 /// converts pascal case to title-cased words, with some exceptions.
@@ -28,7 +23,7 @@ pub fn prettify_pascal_case(string: String) -> String {
     let mut formatted = String::new();
 
     for c in string.chars() {
-        if c.is_uppercase() && formatted.len() > 0 {
+        if c.is_uppercase() && !formatted.is_empty() {
             formatted.push(' ');
         }
         formatted.push(c);
@@ -41,3 +36,29 @@ pub fn prettify_pascal_case(string: String) -> String {
         .replace(" On ", " on ")
         .replace(" A ", " a ")
 }
+
+/// encodes bytes as standard base64, with padding.
+/// ```
+/// assert_eq!(base64_encode(b"anvil"), String::from("YW52aWw="));
+/// ```
+pub fn base64_encode(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+    let mut encoded = String::new();
+    for chunk in bytes.chunks(3) {
+        let n = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |n, (i, byte)| n | (*byte as u32) << (16 - 8 * i));
+
+        for i in 0..4 {
+            if i <= chunk.len() {
+                encoded.push(ALPHABET[(n >> (18 - 6 * i) & 0x3f) as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+
+    encoded
+}
@@ -0,0 +1,330 @@
+//! checks an inventory for problems before it's combined, so the player can fix them first.
+//! errors stop the inventory from being combined at all, while warnings mean something is wasted.
+
+use itertools::Itertools;
+
+use crate::{
+    anvil::Anvil,
+    enchantments::Enchantment,
+    item::{Item, ItemType},
+    lang::Translations,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, PartialOrd)]
+pub enum Severity {
+    /// the inventory can't be combined until this is fixed
+    Error,
+    /// the inventory can be combined, but something is lost or wasted
+    Warning,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Issue {
+    /// a book holds two enchantments that can't go on the same item
+    ConflictingBook(Enchantment, Enchantment),
+    /// the level can't be reached in survival
+    AboveObtainable(Enchantment, u32),
+    /// the book's enchantment can't go on the target, which is given without enchantments
+    UnusableBook(Enchantment, Item),
+    /// there are more copies of this book than can ever be merged
+    DuplicateBook { copies: usize, usable: usize },
+    /// the item can't be combined with the target. both are given without enchantments.
+    MixedTools(Item, Item),
+}
+
+/// a problem with one item of the inventory
+#[derive(Clone, Debug, PartialEq)]
+pub struct Diagnostic {
+    /// the index of the item in the inventory
+    pub item: usize,
+    pub issue: Issue,
+}
+
+impl Issue {
+    pub fn severity(&self) -> Severity {
+        match self {
+            Self::MixedTools(..) => Severity::Error,
+            _ => Severity::Warning,
+        }
+    }
+
+    /// what's wrong, in the given language
+    pub fn message(&self, translations: &Translations) -> String {
+        match self {
+            Self::ConflictingBook(first, second) => translations.format(
+                "calculator.issue.conflicting_book",
+                &[
+                    &translations.enchantment(*first),
+                    &translations.enchantment(*second),
+                ],
+            ),
+            Self::AboveObtainable(enchantment, level) => translations.format(
+                "calculator.issue.above_obtainable",
+                &[&translations.enchantment_with_level(*enchantment, *level)],
+            ),
+            Self::UnusableBook(enchantment, target) => translations.format(
+                "calculator.issue.unusable_book",
+                &[
+                    &translations.enchantment(*enchantment),
+                    &translations.item(target),
+                ],
+            ),
+            Self::DuplicateBook { copies, usable } => translations.format(
+                "calculator.issue.duplicate_book",
+                &[&copies.to_string(), &usable.to_string()],
+            ),
+            Self::MixedTools(item, target) => translations.format(
+                "calculator.issue.mixed_tools",
+                &[&translations.item(item), &translations.item(target)],
+            ),
+        }
+    }
+
+    /// what the player can do about it, in the given language
+    pub fn fix(&self, translations: &Translations) -> String {
+        match self {
+            Self::ConflictingBook(_, second) => translations.format(
+                "calculator.issue.conflicting_book.fix",
+                &[&translations.enchantment(*second)],
+            ),
+            Self::AboveObtainable(enchantment, _) => {
+                translations.format(
+                    "calculator.issue.above_obtainable.fix",
+                    &[&translations
+                        .enchantment_with_level(*enchantment, enchantment.max_obtainable())],
+                )
+            }
+            Self::UnusableBook(enchantment, _) => translations.format(
+                "calculator.issue.unusable_book.fix",
+                &[&translations.enchantment(*enchantment)],
+            ),
+            Self::DuplicateBook { copies, usable } => translations.format(
+                "calculator.issue.duplicate_book.fix",
+                &[&(copies - usable).to_string()],
+            ),
+            Self::MixedTools(..) => translations.get("calculator.issue.mixed_tools.fix"),
+        }
+    }
+}
+
+/// the item without its enchantments or anvil uses, for naming it in an issue
+fn kind_of(item: &Item) -> Item {
+    let mut kind = Item::new(*item.item_type());
+    if let Some(material) = item.material() {
+        kind.set_material(material);
+    }
+
+    kind
+}
+
+/// checks every item of the inventory, returning the problems with the most serious first.
+/// the first item that isn't a book is treated as the target.
+/// levels above what survival allows are only reported if the anvil doesn't allow over-max levels.
+pub fn validate(items: &[Item], anvil: &Anvil) -> Vec<Diagnostic> {
+    let behavior = anvil.behavior();
+    let mut diagnostics = Vec::new();
+    let mut report = |item: usize, issue: Issue| diagnostics.push(Diagnostic { item, issue });
+
+    let target = items
+        .iter()
+        .find(|item| item.item_type() != &ItemType::EnchantedBook);
+
+    for (i, item) in items.iter().enumerate() {
+        let is_book = item.item_type() == &ItemType::EnchantedBook;
+
+        if let Some(target) = target {
+            if !is_book && !item.is_same_kind(target) {
+                report(i, Issue::MixedTools(kind_of(item), kind_of(target)));
+            }
+        }
+
+        for (j, (enchantment, level)) in item.enchantments().iter().enumerate() {
+            if *level > enchantment.max_obtainable() && !anvil.allows_over_max() {
+                report(i, Issue::AboveObtainable(*enchantment, *level));
+            }
+
+            if !is_book {
+                continue;
+            }
+
+            if let Some((earlier, _)) = item.enchantments()[..j]
+                .iter()
+                .find(|(earlier, _)| earlier.conflicts_with(enchantment, behavior))
+            {
+                report(i, Issue::ConflictingBook(*earlier, *enchantment));
+            }
+
+            if let Some(target) = target {
+                if !target.is_compatible(enchantment, behavior) {
+                    report(i, Issue::UnusableBook(*enchantment, kind_of(target)));
+                }
+            }
+        }
+    }
+
+    // merging two equal books only raises the level by one, so only so many copies of a book help
+    let books = items
+        .iter()
+        .enumerate()
+        .filter(|(_, item)| item.item_type() == &ItemType::EnchantedBook)
+        .into_group_map_by(|(_, item)| item.enchantments().clone());
+    for (enchantments, copies) in books.into_iter().sorted_by_key(|(_, copies)| copies[0].0) {
+        let usable = enchantments
+            .iter()
+            .map(|(enchantment, level)| {
+                1usize
+                    << anvil
+                        .max_merged_level(*enchantment)
+                        .saturating_sub(*level)
+                        .min(16)
+            })
+            .min()
+            .unwrap_or(1);
+
+        if copies.len() > usable {
+            // the extra copies are the ones at the end
+            report(
+                copies[usable].0,
+                Issue::DuplicateBook {
+                    copies: copies.len(),
+                    usable,
+                },
+            );
+        }
+    }
+
+    diagnostics.sort_by(|a, b| {
+        a.issue
+            .severity()
+            .partial_cmp(&b.issue.severity())
+            .unwrap()
+            .then(a.item.cmp(&b.item))
+    });
+    diagnostics
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        anvil::Anvil,
+        enchantments::Enchantment,
+        item::{item, ItemType},
+        lang::Language,
+    };
+
+    use super::{validate, Diagnostic, Issue, Severity};
+
+    #[test]
+    fn valid_inventory() {
+        let items = vec![
+            item!(ItemType::Pickaxe),
+            item!(ItemType::EnchantedBook, (Enchantment::Efficiency, 4)),
+            item!(ItemType::EnchantedBook, (Enchantment::Efficiency, 4)),
+            item!(ItemType::EnchantedBook, (Enchantment::Fortune, 3)),
+        ];
+
+        assert_eq!(validate(&items, &Anvil::new_java()), vec![]);
+        assert_eq!(validate(&[], &Anvil::new_java()), vec![]);
+    }
+
+    #[test]
+    fn inventory_issues() {
+        let items = vec![
+            item!(ItemType::Sword),
+            item!(ItemType::Bow),
+            item!(
+                ItemType::EnchantedBook,
+                (Enchantment::SilkTouch, 1),
+                (Enchantment::Fortune, 3)
+            ),
+            item!(ItemType::EnchantedBook, (Enchantment::Protection, 4)),
+            item!(ItemType::EnchantedBook, (Enchantment::Protection, 4)),
+            item!(ItemType::EnchantedBook, (Enchantment::WindBurst, 3)),
+        ];
+
+        let diagnostics = validate(&items, &Anvil::new_java());
+        assert_eq!(
+            diagnostics,
+            vec![
+                Diagnostic {
+                    item: 1,
+                    issue: Issue::MixedTools(item!(ItemType::Bow), item!(ItemType::Sword)),
+                },
+                Diagnostic {
+                    item: 2,
+                    issue: Issue::UnusableBook(Enchantment::SilkTouch, item!(ItemType::Sword)),
+                },
+                Diagnostic {
+                    item: 2,
+                    issue: Issue::ConflictingBook(Enchantment::SilkTouch, Enchantment::Fortune),
+                },
+                Diagnostic {
+                    item: 2,
+                    issue: Issue::UnusableBook(Enchantment::Fortune, item!(ItemType::Sword)),
+                },
+                Diagnostic {
+                    item: 3,
+                    issue: Issue::UnusableBook(Enchantment::Protection, item!(ItemType::Sword)),
+                },
+                Diagnostic {
+                    item: 4,
+                    issue: Issue::UnusableBook(Enchantment::Protection, item!(ItemType::Sword)),
+                },
+                Diagnostic {
+                    item: 4,
+                    issue: Issue::DuplicateBook {
+                        copies: 2,
+                        usable: 1
+                    },
+                },
+                Diagnostic {
+                    item: 5,
+                    issue: Issue::AboveObtainable(Enchantment::WindBurst, 3),
+                },
+                Diagnostic {
+                    item: 5,
+                    issue: Issue::UnusableBook(Enchantment::WindBurst, item!(ItemType::Sword)),
+                },
+            ]
+        );
+
+        assert_eq!(diagnostics[0].issue.severity(), Severity::Error);
+        assert_eq!(diagnostics[1].issue.severity(), Severity::Warning);
+        let english = Language::EnglishUs.translations();
+        assert_eq!(
+            diagnostics[0].issue.message(&english),
+            "a Bow can't be combined with a Diamond Sword"
+        );
+        assert_eq!(
+            diagnostics[6].issue.message(&english),
+            "there are 2 copies of this book, but only 1 can be used"
+        );
+        assert_eq!(diagnostics[6].issue.fix(&english), "remove 1 of the copies");
+        assert_eq!(
+            diagnostics[7].issue.fix(&Language::German.translations()),
+            "senke es auf Windstoß I, die höchste Stufe im Überlebensmodus"
+        );
+
+        // levels made with commands are expected in over-max mode
+        let diagnostics = validate(&items, &Anvil::new_java().with_over_max(true));
+        assert_eq!(diagnostics.len(), 8);
+        assert!(!diagnostics
+            .iter()
+            .any(|diagnostic| matches!(diagnostic.issue, Issue::AboveObtainable(..))));
+
+        // merging over-max books still caps them, so a second copy is no use
+        let mut book = item!(ItemType::EnchantedBook);
+        book.enchant_over_max(Enchantment::Sharpness, 10);
+        let diagnostics = validate(
+            &[item!(ItemType::Sword), book.clone(), book],
+            &Anvil::new_java().with_over_max(true),
+        );
+        assert_eq!(
+            diagnostics.iter().map(|d| &d.issue).collect::<Vec<_>>(),
+            vec![&Issue::DuplicateBook {
+                copies: 2,
+                usable: 1
+            }]
+        );
+    }
+}
@@ -1,23 +1,88 @@
 use action::{Action, ActionComponent};
+use anvil_gui::{start_drag, AnvilGui};
+use std::rc::Rc;
+
+use gloo_events::EventListener;
+use gloo_file::{callbacks::FileReader, File};
+use history::History;
+use itertools::Itertools;
+use js_sys::{Function, Reflect};
 use strum::IntoEnumIterator;
+use wasm_bindgen::{JsCast, JsValue};
+use web_sys::{HtmlInputElement, KeyboardEvent};
 use yew::{
-    classes, function_component, html, AttrValue, Component, Context, Html, MouseEvent, Properties,
+    classes, function_component, html, use_context, AttrValue, Component, Context, ContextProvider,
+    Event, Html, MouseEvent, Properties, TargetCast,
 };
 
 use crate::{
-    anvil::Anvil,
+    anvil::{
+        Anvil, AnvilBehavior, AnvilCombinationRank, AnvilCombinationResults, AnvilStep, AnvilTree,
+        EnchantmentOutcome, LevelLoss, RedundantBooks,
+    },
     enchantments::Enchantment,
-    item::{Item, ItemType},
+    export::{datapack, give_command, mcfunction},
+    import::{items_from_save, parse_item},
+    item::{Item, ItemType, Material, MAX_ANVIL_USES},
+    lang::{Language, Translations},
+    permalink::{self, Setup},
     presets::{presets, Preset},
-    util::to_roman_numerals,
+    util::base64_encode,
+    validate::{validate, Diagnostic, Severity},
 };
 
 mod action;
+mod anvil_gui;
+mod history;
+mod storage;
 
 pub struct App {
     anvil: Anvil,
     source_items: Option<Vec<Item>>,
     selected_item: Option<usize>,
+    /// whether the first item is pinned as the target, so it's never used as a sacrifice
+    target_pinned: bool,
+    import_error: Option<String>,
+    /// the world save currently being read. the read is cancelled if this is dropped.
+    save_reader: Option<FileReader>,
+    /// whether the permalink was copied since the inventory last changed
+    link_copied: bool,
+    /// whether the plan is shown as a checklist to follow at the anvil
+    walkthrough: bool,
+    /// how many steps of the plan have been ticked off in the walkthrough
+    completed_steps: usize,
+    /// the picks of a plan being built by hand, as (target, sacrifice) indices into the items left.
+    /// `None` if the player isn't building one.
+    manual_picks: Option<Vec<(usize, usize)>>,
+    /// the item picked for the left slot of the next manual step
+    manual_target: Option<usize>,
+    /// whether the last manual pick was two items that can't be combined
+    manual_rejected: bool,
+    /// the items put into the left and right slots of the anvil screen. emptied when the inventory changes.
+    anvil_slots: [Option<Item>; 2],
+    /// the name typed into the anvil screen
+    anvil_name: String,
+    /// the best plans for the inventory, from `solve`. worked out again whenever the inventory changes.
+    results: Option<AnvilCombinationResults>,
+    /// the books that can be left out of the inventory, worked out along with `results`
+    redundant: Option<RedundantBooks>,
+    language: Language,
+    /// the names for `language`, shared with every component through a context
+    translations: Rc<Translations>,
+    /// every language in its own name, for the language picker
+    language_names: Vec<(Language, String)>,
+    history: History<Snapshot>,
+    /// listens for the undo and redo shortcuts. they stop working if this is dropped.
+    _shortcuts: EventListener,
+}
+
+/// the state that's restored when an edit is undone
+#[derive(Clone)]
+struct Snapshot {
+    behavior: AnvilBehavior,
+    source_items: Option<Vec<Item>>,
+    selected_item: Option<usize>,
+    target_pinned: bool,
 }
 
 pub enum AppMessage {
@@ -26,6 +91,27 @@ pub enum AppMessage {
     ToggleSelect(usize),
     Action(Action),
     ModifyEnchantment(Enchantment, i32),
+    ModifyAnvilUses(i32),
+    SetMaterial(Material),
+    Import(String),
+    ReadSave(File),
+    ImportSave(Option<Vec<u8>>),
+    SetBehavior(AnvilBehavior),
+    ToggleOverMax,
+    SetLanguage(Language),
+    CopyLink,
+    Undo,
+    Redo,
+    ToggleWalkthrough,
+    ToggleStep(usize),
+    Replan,
+    ToggleManual,
+    PickManual(usize),
+    UndoManual,
+    FillSlot(usize, usize),
+    ClearSlot(usize),
+    Rename(String),
+    LeaveOut(Vec<usize>),
 }
 
 impl App {
@@ -33,11 +119,177 @@ impl App {
         self.source_items.as_ref().map(|items| &items[0])
     }
 
+    /// adds an item to the inventory, keeping tools ahead of books.
+    /// returns the index of the new item, or `None` if it can't be combined with the existing items.
+    fn add_item(&mut self, item: Item) -> Option<usize> {
+        let source_items = self.source_items.get_or_insert_with(Vec::new);
+
+        // item can be added if its kind already exists in the items, or it's an enchanted book
+        let can_add = source_items.is_empty()
+            || item.item_type() == &ItemType::EnchantedBook
+            || source_items
+                .iter()
+                .any(|existing| existing.is_same_kind(&item));
+
+        if !can_add {
+            if source_items.is_empty() {
+                self.source_items = None;
+            }
+            return None;
+        }
+
+        let index = if item.item_type() == &ItemType::EnchantedBook {
+            source_items.push(item);
+            source_items.len() - 1
+        } else {
+            let index = source_items
+                .iter()
+                .position(|item| item.item_type() == &ItemType::EnchantedBook)
+                .unwrap_or(source_items.len());
+            source_items.insert(index, item);
+            index
+        };
+
+        Some(index)
+    }
+
+    /// the problems with the inventory, with the most serious first
+    fn diagnostics(&self) -> Vec<Diagnostic> {
+        validate(
+            self.source_items.as_deref().unwrap_or_default(),
+            &self.anvil,
+        )
+    }
+
+    /// finds the best plans for the inventory, respecting the pinned target.
+    /// returns `None` if there's nothing to combine, or the inventory has errors.
+    fn solve(&self) -> Option<AnvilCombinationResults> {
+        let source_items = self.source_items.clone()?;
+        if self
+            .diagnostics()
+            .iter()
+            .any(|diagnostic| diagnostic.issue.severity() == Severity::Error)
+        {
+            return None;
+        }
+
+        Some(if self.target_pinned {
+            self.anvil.combine_many_with_target(source_items, Some(0))
+        } else {
+            self.anvil.combine_many(source_items)
+        })
+    }
+
+    /// works out the plans again after the inventory or the anvil changed
+    fn refresh_plan(&mut self) {
+        self.results = self.solve();
+        self.redundant = self.results.as_ref().and_then(|_| {
+            self.anvil.redundant_books(
+                self.source_items.clone().unwrap_or_default(),
+                self.target_pinned.then_some(0),
+            )
+        });
+    }
+
+    fn setup(&self) -> Setup {
+        Setup {
+            behavior: self.anvil.behavior(),
+            items: self.source_items.clone().unwrap_or_default(),
+            pinned: self.target_pinned.then_some(0),
+            over_max: self.anvil.allows_over_max(),
+        }
+    }
+
+    /// replaces the edition and inventory with the given setup
+    fn apply_setup(&mut self, setup: Setup) {
+        // items made with commands need the anvil to keep their levels, even if the setup didn't say so
+        let over_max = setup.items.iter().any(|item| {
+            item.enchantments()
+                .iter()
+                .any(|(enchantment, level)| *level > enchantment.max_level())
+        });
+        self.anvil = Anvil::new(setup.behavior).with_over_max(setup.over_max || over_max);
+        self.source_items = None;
+        self.selected_item = None;
+        self.target_pinned = false;
+
+        let pinned = setup.pinned.map(|index| setup.items[index].clone());
+        let skipped = setup
+            .items
+            .into_iter()
+            .filter(|item| self.add_item(item.clone()).is_none())
+            .count();
+        if skipped > 0 {
+            self.import_error = Some(
+                self.translations
+                    .format("calculator.import.skipped", &[&skipped.to_string()]),
+            );
+        }
+
+        if let Some(index) = pinned.and_then(|pinned| {
+            self.source_items
+                .iter()
+                .flatten()
+                .position(|item| item == &pinned)
+        }) {
+            self.pin_target(index);
+        }
+    }
+
+    /// moves the item at the index to the front of the inventory and pins it as the target.
+    /// books can't be pinned, since tools can't be put onto them.
+    fn pin_target(&mut self, index: usize) {
+        let Some(items) = &mut self.source_items else {
+            return;
+        };
+        if items[index].item_type() == &ItemType::EnchantedBook {
+            return;
+        }
+
+        let item = items.remove(index);
+        items.insert(0, item);
+        self.target_pinned = true;
+        if self.selected_item == Some(index) {
+            self.selected_item = Some(0);
+        }
+    }
+
+    /// the address of the page with the current inventory and edition in the fragment
+    fn permalink(&self) -> String {
+        let location = web_sys::window().unwrap().location();
+        let page = format!(
+            "{}{}{}",
+            location.origin().unwrap_or_default(),
+            location.pathname().unwrap_or_default(),
+            location.search().unwrap_or_default(),
+        );
+
+        match (&self.source_items, self.anvil.behavior()) {
+            (None, AnvilBehavior::Java) => page,
+            _ => format!("{page}#{}", permalink::encode(&self.setup())),
+        }
+    }
+
+    fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            behavior: self.anvil.behavior(),
+            source_items: self.source_items.clone(),
+            selected_item: self.selected_item,
+            target_pinned: self.target_pinned,
+        }
+    }
+
+    fn restore(&mut self, snapshot: Snapshot) {
+        self.anvil = Anvil::new(snapshot.behavior).with_over_max(self.anvil.allows_over_max());
+        self.source_items = snapshot.source_items;
+        self.selected_item = snapshot.selected_item;
+        self.target_pinned = snapshot.target_pinned;
+    }
+
     fn selected_item(&self) -> Option<&Item> {
         self.source_items
             .as_ref()
-            .map(|source_items| self.selected_item.map(|selected| &source_items[selected]))
-            .flatten()
+            .and_then(|source_items| self.selected_item.map(|selected| &source_items[selected]))
     }
 }
 
@@ -45,51 +297,185 @@ impl Component for App {
     type Message = AppMessage;
     type Properties = ();
 
-    fn create(_ctx: &Context<Self>) -> Self {
-        Self {
+    fn create(ctx: &Context<Self>) -> Self {
+        let link = ctx.link().clone();
+        let shortcuts = EventListener::new(&web_sys::window().unwrap(), "keydown", move |ev| {
+            let ev: &KeyboardEvent = ev.unchecked_ref();
+
+            // text fields have their own undo
+            let in_input = ev
+                .target()
+                .is_some_and(|target| target.has_type::<HtmlInputElement>());
+            if !(ev.ctrl_key() || ev.meta_key()) || in_input {
+                return;
+            }
+
+            let message = match ev.key().to_lowercase().as_str() {
+                "z" if ev.shift_key() => AppMessage::Redo,
+                "z" => AppMessage::Undo,
+                "y" => AppMessage::Redo,
+                _ => return,
+            };
+            ev.prevent_default();
+            link.send_message(message);
+        });
+
+        // the language picked last time, or the browser's if there's one for it
+        let language = storage::load_language()
+            .or_else(|| {
+                let locale = web_sys::window()?.navigator().language()?;
+                Language::from_id(&locale.to_lowercase().replace('-', "_")).or_else(|| {
+                    Language::iter().find(|language| locale.starts_with(&language.id()[..2]))
+                })
+            })
+            .unwrap_or(Language::EnglishUs);
+
+        let mut app = Self {
             anvil: Anvil::new_java(),
             source_items: None,
             selected_item: None,
+            target_pinned: false,
+            import_error: None,
+            save_reader: None,
+            link_copied: false,
+            walkthrough: false,
+            completed_steps: 0,
+            manual_picks: None,
+            manual_target: None,
+            manual_rejected: false,
+            anvil_slots: [None, None],
+            anvil_name: String::new(),
+            results: None,
+            redundant: None,
+            language,
+            translations: Rc::new(language.translations()),
+            language_names: Language::iter()
+                .map(|language| (language, language.translations().name()))
+                .collect(),
+            history: History::default(),
+            _shortcuts: shortcuts,
+        };
+
+        // a shared inventory in the url takes priority over the one saved from last time
+        let fragment = web_sys::window()
+            .unwrap()
+            .location()
+            .hash()
+            .unwrap_or_default();
+        let restored = if fragment.is_empty() {
+            storage::load().map(Ok)
+        } else {
+            Some(permalink::decode(&fragment))
+        };
+
+        match restored {
+            Some(Ok(setup)) => app.apply_setup(setup),
+            Some(Err(error)) => app.import_error = Some(error.to_string()),
+            None => {}
         }
+        app.refresh_plan();
+
+        app
     }
 
-    fn update(&mut self, _ctx: &Context<Self>, msg: Self::Message) -> bool {
-        match msg {
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+        self.link_copied = matches!(msg, AppMessage::CopyLink);
+
+        // every edit to the inventory or edition can be undone
+        let before = self.snapshot();
+        let over_max = self.anvil.allows_over_max();
+        let is_edit = !matches!(msg, AppMessage::Undo | AppMessage::Redo);
+
+        let render = match msg {
             AppMessage::ApplyPreset(preset) => {
                 self.source_items = Some([preset.items, preset.books].concat());
                 self.selected_item = None;
+                self.target_pinned = false;
                 true
             }
             AppMessage::AddItem(item_type) => {
-                // item can be added if its type already exists in the items, or it's an enchanted book
-                let can_add = self.source_items.as_ref().map_or(true, |items| {
-                    items.iter().any(|item| item.item_type() == &item_type)
-                }) || item_type == ItemType::EnchantedBook;
-
-                if can_add {
-                    if self.source_items.is_none() {
-                        self.source_items = Some(Vec::new());
-                    }
-                    let source_items = self.source_items.as_mut().unwrap();
-                    let index = if item_type == ItemType::EnchantedBook {
-                        source_items.push(Item::new(item_type));
-                        source_items.len() - 1
-                    } else {
-                        let index = source_items
-                            .iter()
-                            .position(|item| item.item_type() == &ItemType::EnchantedBook)
-                            .unwrap_or(source_items.len());
-                        source_items.insert(index, Item::new(item_type));
-                        index
-                    };
+                let mut new_item = Item::new(item_type);
+
+                // items of the same type have to share a material to be combined
+                if let Some(material) = self
+                    .source_items
+                    .iter()
+                    .flatten()
+                    .find(|item| item.item_type() == &item_type)
+                    .and_then(|item| item.material())
+                {
+                    new_item.set_material(material);
+                }
 
+                if let Some(index) = self.add_item(new_item) {
                     self.selected_item = Some(index);
                 }
 
                 true
             }
+            AppMessage::Import(input) => {
+                self.import_error = match parse_item(&input) {
+                    Ok(item) => match self.add_item(item) {
+                        Some(index) => {
+                            self.selected_item = Some(index);
+                            None
+                        }
+                        None => Some(self.translations.get("calculator.import.incompatible")),
+                    },
+                    Err(error) => Some(error.message(&self.translations)),
+                };
+
+                true
+            }
+            AppMessage::ReadSave(file) => {
+                let link = ctx.link().clone();
+                self.save_reader = Some(gloo_file::callbacks::read_as_bytes(&file, move |bytes| {
+                    link.send_message(AppMessage::ImportSave(bytes.ok()))
+                }));
+
+                false
+            }
+            AppMessage::ImportSave(bytes) => {
+                self.save_reader = None;
+
+                let items = match bytes {
+                    Some(bytes) => {
+                        items_from_save(&bytes).map_err(|error| error.message(&self.translations))
+                    }
+                    None => Err(self.translations.get("calculator.import.save.unreadable")),
+                };
+                self.import_error = match items {
+                    Ok((items, unreadable)) => {
+                        let total = items.len() + unreadable;
+                        // books go last so the first tool decides which kind of item is being enchanted
+                        let skipped = unreadable
+                            + items
+                                .into_iter()
+                                .sorted_by_key(|item| item.item_type() == &ItemType::EnchantedBook)
+                                .filter(|item| self.add_item(item.clone()).is_none())
+                                .count();
+
+                        if total == 0 {
+                            Some(self.translations.get("calculator.import.empty"))
+                        } else if skipped > 0 {
+                            Some(
+                                self.translations.format(
+                                    "calculator.import.save.skipped",
+                                    &[&skipped.to_string()],
+                                ),
+                            )
+                        } else {
+                            None
+                        }
+                    }
+                    Err(error) => Some(error),
+                };
+                self.selected_item = None;
+
+                true
+            }
             AppMessage::ToggleSelect(index) => {
-                if self.selected_item.map_or(false, |sel| sel == index) {
+                if self.selected_item == Some(index) {
                     self.selected_item = None;
                 } else {
                     self.selected_item = Some(index);
@@ -98,21 +484,57 @@ impl Component for App {
                 true
             }
             AppMessage::Action(action) => {
+                let Some(selected) = self.selected_item else {
+                    return false;
+                };
+                let behavior = self.anvil.behavior();
+                let target_type = *self.target_item().unwrap().item_type();
+                let items = self.source_items.as_mut().unwrap();
+
                 match action {
                     Action::Remove => {
-                        if let Some(selected) = &self.selected_item {
-                            self.source_items.as_mut().unwrap().remove(*selected);
-
-                            let items = self.source_items.as_ref().unwrap();
-                            if items.is_empty() {
-                                self.source_items = None;
-                                self.selected_item = None;
-                            } else if items.len() == *selected {
-                                self.selected_item = Some(items.len() - 1);
-                            }
+                        items.remove(selected);
+                        if selected == 0 {
+                            self.target_pinned = false;
+                        }
+
+                        if items.is_empty() {
+                            self.source_items = None;
+                            self.selected_item = None;
+                        } else if items.len() == selected {
+                            self.selected_item = Some(items.len() - 1);
+                        }
+                    }
+                    Action::Duplicate => {
+                        let item = items[selected].clone();
+                        self.selected_item = self.add_item(item);
+                    }
+                    Action::ClearEnchantments => items[selected].clear_enchantments(),
+                    Action::MaxEnchantments => {
+                        items[selected].max_enchantments(&target_type, behavior)
+                    }
+                    // right clicking goes the other way, through `AppMessage::ModifyAnvilUses`
+                    Action::PriorWork => modify_anvil_uses(&mut items[selected], 1),
+                    Action::PinTarget => {
+                        if self.target_pinned && selected == 0 {
+                            self.target_pinned = false;
+                        } else {
+                            // only the first item can be pinned, so the old pin is replaced
+                            self.pin_target(selected);
                         }
                     }
+                    Action::SplitBook => {
+                        let books = items[selected].split_into_books();
+                        items.splice(selected..=selected, books);
+                    }
+                }
+                true
+            }
+            AppMessage::ModifyAnvilUses(change) => {
+                if let Some(selected) = self.selected_item {
+                    modify_anvil_uses(&mut self.source_items.as_mut().unwrap()[selected], change);
                 }
+
                 true
             }
             AppMessage::ModifyEnchantment(enchantment, level_change) => {
@@ -123,78 +545,405 @@ impl Component for App {
                         .unwrap()
                         .get_mut(*selected)
                         .unwrap();
-                    let level = item.level_of(enchantment);
-                    let new_level = level
+                    let max_level = self.anvil.max_level(enchantment);
+                    // going past either end wraps around to the other
+                    let new_level = match item
+                        .level_of(enchantment)
                         .unwrap_or(0)
                         .checked_add_signed(level_change)
-                        .unwrap_or(enchantment.max_level());
-                    item.enchant(enchantment, new_level);
+                    {
+                        Some(level) if level <= max_level => level,
+                        Some(_) => 0,
+                        None => max_level,
+                    };
+                    item.enchant_over_max(enchantment, new_level);
+                }
+
+                true
+            }
+            AppMessage::Undo => {
+                let snapshot = self.history.undo(before.clone());
+                self.restore(snapshot);
+
+                true
+            }
+            AppMessage::Redo => {
+                let snapshot = self.history.redo(before.clone());
+                self.restore(snapshot);
+
+                true
+            }
+            AppMessage::ToggleWalkthrough => {
+                self.walkthrough = !self.walkthrough;
+                self.completed_steps = 0;
+
+                true
+            }
+            AppMessage::ToggleStep(index) => {
+                // ticking a step ticks everything before it, and unticking it unticks everything after
+                if self.completed_steps == index + 1 {
+                    self.completed_steps = index;
+                } else {
+                    self.completed_steps = index + 1;
+                }
+
+                true
+            }
+            AppMessage::Replan => {
+                let Some(results) = &self.results else {
+                    return false;
+                };
+                let items = self
+                    .anvil
+                    .items_after(results.lowest_solution.clone(), self.completed_steps);
+
+                // the pinned item is never a sacrifice, so what it became stays at the front
+                self.apply_setup(Setup {
+                    behavior: self.anvil.behavior(),
+                    items,
+                    pinned: self.target_pinned.then_some(0),
+                    over_max: self.anvil.allows_over_max(),
+                });
+
+                true
+            }
+            AppMessage::ToggleManual => {
+                self.manual_picks = match self.manual_picks {
+                    Some(_) => None,
+                    None => Some(Vec::new()),
+                };
+                self.manual_target = None;
+                self.manual_rejected = false;
+
+                true
+            }
+            AppMessage::PickManual(index) => {
+                let (Some(picks), Some(source_items)) =
+                    (&mut self.manual_picks, &self.source_items)
+                else {
+                    return false;
+                };
+
+                self.manual_rejected = false;
+                match self.manual_target.take() {
+                    // clicking the left item again puts it back
+                    Some(target) if target == index => {}
+                    Some(target) => {
+                        picks.push((target, index));
+                        if self.anvil.evaluate(source_items.clone(), picks).is_none() {
+                            picks.pop();
+                            self.manual_rejected = true;
+                        }
+                    }
+                    None => self.manual_target = Some(index),
+                }
+
+                true
+            }
+            AppMessage::UndoManual => {
+                if let Some(picks) = &mut self.manual_picks {
+                    picks.pop();
                 }
+                self.manual_target = None;
+                self.manual_rejected = false;
 
                 true
             }
+            AppMessage::FillSlot(slot, index) => {
+                let Some(item) = self.source_items.iter().flatten().nth(index) else {
+                    return false;
+                };
+                self.anvil_slots[slot] = Some(item.clone());
+
+                true
+            }
+            AppMessage::ClearSlot(slot) => self.anvil_slots[slot].take().is_some(),
+            AppMessage::LeaveOut(books) => {
+                let Some(items) = &mut self.source_items else {
+                    return false;
+                };
+
+                // the books are in order, so removing from the back keeps the other indices valid
+                for book in books.into_iter().rev() {
+                    items.remove(book);
+                }
+                self.selected_item = None;
+
+                true
+            }
+            AppMessage::Rename(name) => {
+                self.anvil_name = name;
+
+                true
+            }
+            AppMessage::SetBehavior(behavior) => {
+                self.anvil = Anvil::new(behavior).with_over_max(self.anvil.allows_over_max());
+
+                true
+            }
+            AppMessage::ToggleOverMax => {
+                self.anvil =
+                    Anvil::new(self.anvil.behavior()).with_over_max(!self.anvil.allows_over_max());
+
+                true
+            }
+            AppMessage::SetLanguage(language) => {
+                self.language = language;
+                self.translations = Rc::new(language.translations());
+                storage::save_language(language);
+
+                true
+            }
+            AppMessage::CopyLink => {
+                let navigator = web_sys::window().unwrap().navigator();
+
+                // `Navigator::clipboard` is still an unstable web-sys api, so it's looked up by hand
+                let write_text =
+                    Reflect::get(&navigator, &"clipboard".into()).and_then(|clipboard| {
+                        let write_text: Function =
+                            Reflect::get(&clipboard, &"writeText".into())?.dyn_into()?;
+                        write_text.call1(&clipboard, &self.permalink().into())
+                    });
+                self.link_copied = write_text.is_ok();
+
+                true
+            }
+            AppMessage::SetMaterial(material) => {
+                // every item that isn't a book has the same type, so they all change together
+                if let Some(source_items) = &mut self.source_items {
+                    for item in source_items {
+                        item.set_material(material);
+                    }
+                }
+
+                true
+            }
+        };
+
+        // solving is slow, so it's only done again when something it depends on changed
+        if before.behavior != self.anvil.behavior()
+            || before.source_items != self.source_items
+            || before.target_pinned != self.target_pinned
+            || over_max != self.anvil.allows_over_max()
+        {
+            self.refresh_plan();
+        }
+
+        if before.source_items != self.source_items {
+            // the anvil screen holds copies, which would be out of date
+            self.anvil_slots = [None, None];
+        }
+
+        if before.behavior != self.anvil.behavior()
+            || before.source_items != self.source_items
+            || before.target_pinned != self.target_pinned
+        {
+            // the plan changed, so the walkthrough and the manual plan start over
+            self.completed_steps = 0;
+            if let Some(picks) = &mut self.manual_picks {
+                picks.clear();
+            }
+            self.manual_target = None;
+
+            if is_edit {
+                self.history.record(before);
+            }
         }
+
+        render
+    }
+
+    fn rendered(&mut self, _ctx: &Context<Self>, _first_render: bool) {
+        // keep the url pointing at the current inventory, without adding to the history
+        let _ = web_sys::window().unwrap().history().and_then(|history| {
+            history.replace_state_with_url(&JsValue::NULL, "", Some(&self.permalink()))
+        });
+
+        storage::save(&self.setup());
     }
 
     fn view(&self, ctx: &Context<Self>) -> Html {
-        let result_html = match &self.source_items {
-            Some(source_items) => {
-                let results = self.anvil.combine_many(source_items.clone());
-
-                let mut items = results.lowest_solution;
-                let mut rows = Vec::new();
-
-                while items.len() > 1 {
-                    let mut new_items = Vec::new();
-
-                    for _ in 0..items.len() / 2 {
-                        let item1 = items.remove(0);
-                        let item2 = items.remove(0);
-
-                        let (cost, result, _) =
-                            self.anvil.combine(item1.clone(), item2.clone()).unwrap();
-                        new_items.push(result.clone());
-
-                        rows.push(html! {
-                            <>
-                                <div class="anvil items">
-                                    <ItemComponent item={item1} />
-                                    <div />
-                                    <ItemComponent item={item2} />
-                                    <div />
-                                    <ItemComponent item={result} />
-                                </div>
-                                <span class="green-xp">
-                                    {format!("Enchantment Cost: {cost}")}
+        let result_html = match &self.results {
+            Some(results) => {
+                let steps = self.anvil.steps(results.lowest_solution.clone());
+                let step_count = steps.len();
+
+                let mut levels_spent = 0;
+                let rows = steps.into_iter().enumerate().map(|(i, step)| {
+                    levels_spent += step.cost;
+
+                    let cost = self.cost_html(&step);
+                    let done = self.walkthrough && i < self.completed_steps;
+                    let current = self.walkthrough && i == self.completed_steps;
+
+                    html! {
+                        <div class={classes!("step", done.then_some("done"), current.then_some("current"))}>
+                            <div class="anvil items">
+                                <ItemComponent item={step.target.clone()} />
+                                <div />
+                                <ItemComponent item={step.sacrifice.clone()} />
+                                <div />
+                                <ItemComponent item={step.result} />
+                            </div>
+                            {cost}
+                            {losses_html(&step.losses, &self.translations)}
+                            if self.walkthrough {
+                                <span class="slots">
+                                    {self.translations.format(
+                                        "calculator.step.slots",
+                                        &[&self.translations.item(&step.target), &self.translations.item(&step.sacrifice)],
+                                    )}
+                                </span>
+                                <span class="slots">
+                                    {self.translations.format("calculator.step.spent", &[&levels_spent.to_string()])}
                                 </span>
-                            </>
-                        });
+                                <span
+                                    class={classes!("tick", if done { "green" } else { "light-blue" })}
+                                    onclick={ctx.link().callback(move |_| AppMessage::ToggleStep(i))}
+                                >
+                                    {self.translations.get(if done { "calculator.step.done" } else { "calculator.step.mark_done" })}
+                                </span>
+                            }
+                            <details class="commands">
+                                <summary>{self.translations.get("calculator.step.commands")}</summary>
+                                <code>{give_command(&step.target)}</code>
+                                <code>{give_command(&step.sacrifice)}</code>
+                            </details>
+                        </div>
                     }
-
-                    items = [new_items, items].concat();
-                }
+                });
 
                 html! {
                     <div class="container center">
-                        <h1>{"Repair & Name"}</h1>
+                        <h1>{self.translations.get("container.repair")}</h1>
+                        <span
+                            class={classes!("walkthrough-toggle", self.walkthrough.then_some("selected"))}
+                            onclick={ctx.link().callback(|_| AppMessage::ToggleWalkthrough)}
+                        >
+                            {self.translations.get(if self.walkthrough {
+                                "calculator.walkthrough.whole_plan"
+                            } else {
+                                "calculator.walkthrough.follow"
+                            })}
+                        </span>
+                        <span
+                            class={classes!("walkthrough-toggle", self.manual_picks.is_some().then_some("selected"))}
+                            onclick={ctx.link().callback(|_| AppMessage::ToggleManual)}
+                        >
+                            {self.translations.get(if self.manual_picks.is_some() {
+                                "calculator.manual.hide"
+                            } else {
+                                "calculator.manual.show"
+                            })}
+                        </span>
                         <div class="rows">{for rows}</div>
+                        if let Some(tree) = self.anvil.tree(results.lowest_solution.clone()) {
+                            <details class="commands tree">
+                                <summary>{self.translations.get("calculator.tree")}</summary>
+                                {tree_html(&tree, &self.translations)}
+                            </details>
+                        }
+                        if self.walkthrough && self.completed_steps > 0 && self.completed_steps < step_count {
+                            <span
+                                class="walkthrough-toggle"
+                                onclick={ctx.link().callback(|_| AppMessage::Replan)}
+                            >
+                                {self.translations.get("calculator.replan")}
+                            </span>
+                        }
                         <h1 class="green-xp">
-                            {format!(
-                                "Total Cost: {} (saves {})",
-                                results.lowest_cost,
-                                results.highest_cost - results.lowest_cost
+                            {self.translations.format(
+                                "calculator.total_cost",
+                                &[
+                                    &results.lowest_cost.to_string(),
+                                    &(results.highest_cost - results.lowest_cost).to_string(),
+                                ],
                             )}
                         </h1>
+                        if let Some(redundant) = &self.redundant {
+                            <span class="note yellow">
+                                {self.translations.format(
+                                    if redundant.books.len() == 1 {
+                                        "calculator.redundant.one"
+                                    } else {
+                                        "calculator.redundant.many"
+                                    },
+                                    &[
+                                        &redundant.books.iter().filter_map(|book| {
+                                            self.source_items.iter().flatten().nth(*book)
+                                        }).map(|book| {
+                                            book.enchantments().iter().map(|(enchantment, level)| {
+                                                self.translations.enchantment_with_level(*enchantment, *level)
+                                            }).join(" + ")
+                                        }).join(", "),
+                                        &redundant.levels_saved.to_string(),
+                                    ],
+                                )}
+                            </span>
+                            <span
+                                class="walkthrough-toggle"
+                                onclick={ctx.link().callback({
+                                    let books = redundant.books.clone();
+                                    move |_| AppMessage::LeaveOut(books.clone())
+                                })}
+                            >
+                                {self.translations.get(if redundant.books.len() == 1 {
+                                    "calculator.redundant.leave_out.one"
+                                } else {
+                                    "calculator.redundant.leave_out.many"
+                                })}
+                            </span>
+                        }
+                        if results.rank == AnvilCombinationRank::Flawed {
+                            <span class="note red">
+                                {self.translations.get("calculator.flawed")}
+                            </span>
+                        }
+                        <div class="downloads">
+                            <a
+                                download="anvil_plan.mcfunction"
+                                href={format!(
+                                    "data:text/plain;base64,{}",
+                                    base64_encode(mcfunction(&self.anvil, &results.lowest_solution).as_bytes())
+                                )}
+                            >
+                                {self.translations.get("calculator.download.mcfunction")}
+                            </a>
+                            <a
+                                download="anvil_plan.zip"
+                                href={format!(
+                                    "data:application/zip;base64,{}",
+                                    base64_encode(&datapack(&self.anvil, &results.lowest_solution))
+                                )}
+                            >
+                                {self.translations.get("calculator.download.datapack")}
+                            </a>
+                            <a
+                                href={self.permalink()}
+                                onclick={ctx.link().callback(|ev: MouseEvent| {
+                                    ev.prevent_default();
+                                    AppMessage::CopyLink
+                                })}
+                            >
+                                {self.translations.get(if self.link_copied { "calculator.link.copied" } else { "calculator.link.copy" })}
+                            </a>
+                        </div>
                     </div>
                 }
             }
             None => html! {},
         };
 
+        let manual_html = match (&self.manual_picks, &self.results) {
+            (Some(picks), Some(results)) => self.manual_plan_html(ctx, picks, results),
+            _ => html! {},
+        };
+
+        let diagnostics = self.diagnostics();
         let item_html = html! {
             <>
                 <div class="container">
-                    <h1>{"Presets"}</h1>
+                    <h1>{self.translations.get("calculator.presets")}</h1>
                     <div class="items">
                         {for presets().into_iter().map(|preset| {
                             // no i dont know why im cloning twice.
@@ -209,7 +958,7 @@ impl Component for App {
                         })}
                     </div>
 
-                    <h1>{"Add Items"}</h1>
+                    <h1>{self.translations.get("calculator.add_items")}</h1>
                     <div class="items">
                         {for ItemType::iter().map(|item_type| html! {
                             <div
@@ -219,41 +968,212 @@ impl Component for App {
                             </div>
                         })}
                     </div>
+
+                    <h1>{self.translations.get("calculator.edition")}</h1>
+                    <div class="editions">
+                        {for [AnvilBehavior::Java, AnvilBehavior::Bedrock].into_iter().map(|behavior| html! {
+                            <span
+                                class={classes!((self.anvil.behavior() == behavior).then_some("selected"))}
+                                onclick={ctx.link().callback(move |_| AppMessage::SetBehavior(behavior))}
+                            >
+                                {self.translations.get(&format!("calculator.edition.{behavior:?}").to_lowercase())}
+                            </span>
+                        })}
+                        <span
+                            class={classes!(self.anvil.allows_over_max().then_some("selected"))}
+                            title={self.translations.get("calculator.over_max.tooltip")}
+                            onclick={ctx.link().callback(|_| AppMessage::ToggleOverMax)}
+                        >
+                            {self.translations.get("calculator.over_max")}
+                        </span>
+                    </div>
+
+                    <h1>{self.translations.get("options.language").trim_end_matches(['.', '…', ' '])}</h1>
+                    <div class="editions">
+                        {for self.language_names.iter().map(|(language, name)| {
+                            let language = *language;
+                            html! {
+                                <span
+                                    class={classes!((self.language == language).then_some("selected"))}
+                                    onclick={ctx.link().callback(move |_| AppMessage::SetLanguage(language))}
+                                >
+                                    {name}
+                                </span>
+                            }
+                        })}
+                    </div>
                 </div>
 
                 <div class="container">
-                    <h1>{"Inventory"}</h1>
+                    <h1>{self.translations.get("container.inventory")}</h1>
+                    <div class="history">
+                        <span
+                            class={classes!((!self.history.can_undo()).then_some("disabled"))}
+                            title="Ctrl+Z"
+                            onclick={ctx.link().callback(|_| AppMessage::Undo)}
+                        >
+                            {self.translations.get("calculator.undo")}
+                        </span>
+                        <span
+                            class={classes!((!self.history.can_redo()).then_some("disabled"))}
+                            title="Ctrl+Shift+Z"
+                            onclick={ctx.link().callback(|_| AppMessage::Redo)}
+                        >
+                            {self.translations.get("calculator.redo")}
+                        </span>
+                    </div>
 
                     if let Some(source_items) = &self.source_items {
                         <div class="items">
                             {for source_items.iter().enumerate().map(|(i, item)| html! {
                                 <div
+                                    draggable="true"
+                                    ondragstart={move |ev| start_drag(ev, i)}
                                     onclick={ctx.link().callback(move |_| AppMessage::ToggleSelect(i))}
                                 >
                                     <ItemComponent
                                         item={item.clone()}
-                                        hint={"Click to edit"}
+                                        hint={self.translations.get("calculator.inventory.hint")}
                                         selected={self.selected_item == Some(i)}
+                                        pinned={self.target_pinned && i == 0}
                                     />
                                 </div>
                             })}
                         </div>
                     }
 
+                    <input
+                        class="import"
+                        placeholder={self.translations.get("calculator.import.placeholder")}
+                        onchange={ctx.link().callback(|ev: Event| {
+                            let input: HtmlInputElement = ev.target_unchecked_into();
+                            let value = input.value();
+                            input.set_value("");
+
+                            AppMessage::Import(value)
+                        })}
+                    />
+                    <label class="import-save">
+                        {self.translations.get("calculator.import.save")}
+                        <input
+                            type="file"
+                            accept=".dat"
+                            onchange={ctx.link().batch_callback(|ev: Event| {
+                                let input: HtmlInputElement = ev.target_unchecked_into();
+                                let file = input.files().and_then(|files| files.get(0));
+                                input.set_value("");
+
+                                file.map(|file| AppMessage::ReadSave(File::from(file)))
+                            })}
+                        />
+                    </label>
+                    if let Some(error) = &self.import_error {
+                        <span class="import-error red">{error}</span>
+                    }
+
+                    if !diagnostics.is_empty() {
+                        <h1>{self.translations.get("calculator.problems")}</h1>
+                        <div class="diagnostics">
+                            {for diagnostics.iter().map(|diagnostic| {
+                                let item = diagnostic.item;
+                                let class = match diagnostic.issue.severity() {
+                                    Severity::Error => "red",
+                                    Severity::Warning => "yellow",
+                                };
+                                let name = self.source_items.iter().flatten().nth(item).map(|item| self.translations.item(item));
+
+                                html! {
+                                    <span
+                                        class={class}
+                                        title={self.translations.get("calculator.problems.select")}
+                                        onclick={ctx.link().callback(move |_| AppMessage::ToggleSelect(item))}
+                                    >
+                                        {self.translations.format(
+                                            "calculator.problems.entry",
+                                            &[
+                                                &name.unwrap_or_default(),
+                                                &diagnostic.issue.message(&self.translations),
+                                                &diagnostic.issue.fix(&self.translations),
+                                            ],
+                                        )}
+                                    </span>
+                                }
+                            })}
+                        </div>
+                    }
+
                     if let Some(selected_item) = &self.selected_item() {
-                        <h1>{"Actions"}</h1>
+                        <h1>{self.translations.get("calculator.actions")}</h1>
                         <div class="items">
-                            <div onclick={ctx.link().callback(move |_| AppMessage::Action(Action::Remove))}>
-                                <ActionComponent action={Action::Remove} />
+                            {for [
+                                Action::Remove,
+                                Action::Duplicate,
+                                Action::ClearEnchantments,
+                                Action::MaxEnchantments,
+                            ].into_iter().map(|action| html! {
+                                <div onclick={ctx.link().callback(move |_| AppMessage::Action(action))}>
+                                    <ActionComponent {action} />
+                                </div>
+                            })}
+                            <div
+                                onclick={ctx.link().callback(|_| AppMessage::Action(Action::PriorWork))}
+                                oncontextmenu={ctx.link().callback(|ev: MouseEvent| {
+                                    ev.prevent_default();
+                                    AppMessage::ModifyAnvilUses(-1)
+                                })}
+                            >
+                                <ActionComponent
+                                    action={Action::PriorWork}
+                                    level={Some(selected_item.anvil_uses()).filter(|uses| *uses > 0)}
+                                />
                             </div>
+                            if selected_item.item_type() != &ItemType::EnchantedBook {
+                                <div onclick={ctx.link().callback(|_| AppMessage::Action(Action::PinTarget))}>
+                                    <ActionComponent
+                                        action={Action::PinTarget}
+                                        selected={self.target_pinned && self.selected_item == Some(0)}
+                                    />
+                                </div>
+                            }
+                            if selected_item.item_type() == &ItemType::EnchantedBook
+                                && selected_item.enchantments().len() > 1
+                            {
+                                <div onclick={ctx.link().callback(|_| AppMessage::Action(Action::SplitBook))}>
+                                    <ActionComponent action={Action::SplitBook} />
+                                </div>
+                            }
                         </div>
 
-                        <h1>{"Enchantments"}</h1>
+                        if !selected_item.item_type().materials().is_empty() {
+                            <h1>{self.translations.get("calculator.material")}</h1>
+                            <div class="items">
+                                {for selected_item.item_type().materials().iter().map(|material| {
+                                    let material = *material;
+                                    let mut item = Item::new(*selected_item.item_type());
+                                    item.set_material(material);
+
+                                    html! {
+                                        <div
+                                            onclick={ctx.link().callback(move |_| AppMessage::SetMaterial(material))}
+                                        >
+                                            <ItemComponent
+                                                {item}
+                                                details={true}
+                                                selected={selected_item.material() == Some(material)}
+                                            />
+                                        </div>
+                                    }
+                                })}
+                            </div>
+                        }
+
+                        <h1>{self.translations.get("calculator.enchantments")}</h1>
 
                         <div class="items">
                             {for Enchantment::friendly_sort_with(
-                                selected_item.compatible_enchantments().into_iter(),
-                                self.target_item().unwrap()
+                                selected_item.compatible_enchantments(self.anvil.behavior()),
+                                self.target_item().unwrap(),
+                                self.anvil.behavior(),
                             ).map(|enchant| html! {
                                 <div
                                     onclick={ctx.link().callback(move |_| AppMessage::ModifyEnchantment(enchant, 1))}
@@ -271,25 +1191,254 @@ impl Component for App {
                         </div>
                     }
                 </div>
+
+                <div class="container">
+                    <h1>{self.translations.get("block.minecraft.anvil")}</h1>
+                    <AnvilGui
+                        behavior={self.anvil.behavior()}
+                        over_max={self.anvil.allows_over_max()}
+                        slots={self.anvil_slots.clone()}
+                        name={self.anvil_name.clone()}
+                        ondrop={ctx.link().callback(|(slot, index)| AppMessage::FillSlot(slot, index))}
+                        onclear={ctx.link().callback(AppMessage::ClearSlot)}
+                        onrename={ctx.link().callback(AppMessage::Rename)}
+                    />
+                </div>
             </>
         };
 
         html! {
-            <>
+            <ContextProvider<Rc<Translations>> context={self.translations.clone()}>
                 <div>{item_html}</div>
 
-                <div>{result_html}</div>
+                <div>
+                    {result_html}
+                    {manual_html}
+                </div>
 
                 <footer>
                     <a href="https://github.com/EnyCode/anvil/" target="_blank">
-                        {"this project is OPEN SOURCE!"}
+                        {self.translations.get("calculator.open_source")}
                     </a>
                 </footer>
-            </>
+            </ContextProvider<Rc<Translations>>>
+        }
+    }
+}
+
+impl App {
+    /// shows the cost of a step, with where every level comes from when it's hovered
+    fn cost_html(&self, step: &AnvilStep) -> Html {
+        let breakdown = self
+            .anvil
+            .combine_explained(step.target.clone(), step.sacrifice.clone())
+            .map(|(_, _, _, breakdown)| breakdown);
+
+        html! {
+            <span class="green-xp breakdown">
+                {self.translations.format("container.repair.cost", &[&step.cost.to_string()])}
+                if let Some(breakdown) = breakdown {
+                    <span class="tooltip">
+                        <span>
+                            {self.translations.format("calculator.cost.target_penalty", &[&breakdown.target_penalty.to_string()])}
+                        </span>
+                        <span>
+                            {self.translations.format("calculator.cost.sacrifice_penalty", &[&breakdown.sacrifice_penalty.to_string()])}
+                        </span>
+                        {for breakdown.enchantments.iter().map(|cost| {
+                            let source = if cost.from_book { "book" } else { "item" };
+                            let multiplier = cost.multiplier.to_string();
+                            let reason = match cost.outcome {
+                                EnchantmentOutcome::Added => self
+                                    .translations
+                                    .format(&format!("calculator.cost.added.{source}"), &[&multiplier]),
+                                EnchantmentOutcome::Merged { .. } => self
+                                    .translations
+                                    .format(&format!("calculator.cost.merged.{source}"), &[&multiplier]),
+                                EnchantmentOutcome::Conflicting => self.translations.get("calculator.cost.conflicting"),
+                                EnchantmentOutcome::Incompatible => self.translations.get("calculator.cost.incompatible"),
+                            };
+
+                            html! {
+                                <span class={classes!((cost.outcome == EnchantmentOutcome::Conflicting
+                                    || cost.outcome == EnchantmentOutcome::Incompatible).then_some("red"))}>
+                                    {format!(
+                                        "{} ({reason}): {}",
+                                        self.translations.enchantment_with_level(cost.enchantment, cost.level),
+                                        cost.cost
+                                    )}
+                                </span>
+                            }
+                        })}
+                    </span>
+                }
+            </span>
+        }
+    }
+
+    /// shows a plan the player is building by hand, and how it compares to the best plan
+    fn manual_plan_html(
+        &self,
+        ctx: &Context<Self>,
+        picks: &[(usize, usize)],
+        results: &AnvilCombinationResults,
+    ) -> Html {
+        let Some(plan) = self
+            .source_items
+            .clone()
+            .and_then(|source_items| self.anvil.evaluate(source_items, picks))
+        else {
+            return html! {};
+        };
+
+        let rows = plan.steps.iter().map(|(step, _)| {
+            html! {
+                <div class="step">
+                    <div class="anvil items">
+                        <ItemComponent item={step.target.clone()} />
+                        <div />
+                        <ItemComponent item={step.sacrifice.clone()} />
+                        <div />
+                        <ItemComponent item={step.result.clone()} />
+                    </div>
+                    if step.is_too_expensive() {
+                        <span class="too-expensive red">
+                            {self.translations.get("container.repair.expensive")}
+                        </span>
+                    } else {
+                        {self.cost_html(step)}
+                    }
+                    {losses_html(&step.losses, &self.translations)}
+                </div>
+            }
+        });
+
+        let total_cost = plan.total_cost();
+        let saved = results.lowest_cost.saturating_sub(total_cost).to_string();
+        let comparison = match total_cost.checked_sub(results.lowest_cost) {
+            _ if !plan.is_finished() => self.translations.get("calculator.manual.pick"),
+            Some(0) => self.translations.get("calculator.manual.same"),
+            Some(extra) => self
+                .translations
+                .format("calculator.manual.more", &[&extra.to_string()]),
+            // the solver only tries one way of pairing each order, so a plan built by hand can beat it
+            None if plan.steps.iter().all(|(step, _)| step.losses.is_empty()) => self
+                .translations
+                .format("calculator.manual.less", &[&saved]),
+            None => self
+                .translations
+                .format("calculator.manual.less_lossy", &[&saved]),
+        };
+
+        html! {
+            <div class="container center">
+                <h1>{self.translations.get("calculator.manual")}</h1>
+                <div class="rows">{for rows}</div>
+                if !plan.is_finished() {
+                    <div class="items">
+                        {for plan.remaining.iter().enumerate().map(|(i, item)| html! {
+                            <div onclick={ctx.link().callback(move |_| AppMessage::PickManual(i))}>
+                                <ItemComponent
+                                    item={item.clone()}
+                                    hint={self.translations.get(if self.manual_target.is_some() {
+                                        "calculator.slot.right"
+                                    } else {
+                                        "calculator.slot.left"
+                                    })}
+                                    selected={self.manual_target == Some(i)}
+                                />
+                            </div>
+                        })}
+                    </div>
+                }
+                if self.manual_rejected {
+                    <span class="note red">{self.translations.get("calculator.manual.rejected")}</span>
+                }
+                if !picks.is_empty() {
+                    <span
+                        class="walkthrough-toggle"
+                        onclick={ctx.link().callback(|_| AppMessage::UndoManual)}
+                    >
+                        {self.translations.get("calculator.manual.undo")}
+                    </span>
+                }
+                <h1 class="green-xp">
+                    {self.translations.format("calculator.manual.total_cost", &[&total_cost.to_string()])}
+                </h1>
+                <span class="note">{comparison}</span>
+            </div>
         }
     }
 }
 
+/// adds to or removes from the anvil uses of an item, up to `MAX_ANVIL_USES`
+fn modify_anvil_uses(item: &mut Item, change: i32) {
+    let anvil_uses = item
+        .anvil_uses()
+        .saturating_add_signed(change)
+        .min(MAX_ANVIL_USES);
+    item.set_anvil_uses(anvil_uses);
+}
+
+/// warns about every enchantment level a step throws away
+fn losses_html(losses: &[LevelLoss], translations: &Translations) -> Html {
+    let warnings = losses.iter().map(|loss| match loss {
+        LevelLoss::Conflicting { enchantment, level } => translations.format(
+            "calculator.loss.conflicting",
+            &[&translations.enchantment_with_level(*enchantment, *level)],
+        ),
+        LevelLoss::Incompatible { enchantment, level } => translations.format(
+            "calculator.loss.incompatible",
+            &[&translations.enchantment_with_level(*enchantment, *level)],
+        ),
+        LevelLoss::Unmerged {
+            enchantment,
+            kept,
+            wasted,
+        } => translations.format(
+            "calculator.loss.unmerged",
+            &[
+                &translations.enchantment_with_level(*enchantment, *wasted),
+                &translations.enchantment_with_level(*enchantment, *kept),
+            ],
+        ),
+    });
+
+    html! {
+        {for warnings.map(|warning| html! {
+            <span class="slots red">{warning}</span>
+        })}
+    }
+}
+
+/// draws a merge and everything that went into it, with the final item at the top.
+/// each merge shows its cost, and each branch shows the work penalty the item brings with it.
+fn tree_html(tree: &AnvilTree, translations: &Translations) -> Html {
+    let branch = |child: &AnvilTree| {
+        html! {
+            <div class="branch">
+                <span title={translations.get("calculator.tree.penalty")}>
+                    {format!("+{}", child.item().work_penalty())}
+                </span>
+                {tree_html(child, translations)}
+            </div>
+        }
+    };
+
+    html! {
+        <div class="node">
+            <ItemComponent item={tree.item().clone()} />
+            if let AnvilTree::Merge { target, sacrifice, cost, .. } = tree {
+                <span class="green-xp">{cost}</span>
+                <div class="branches">
+                    {branch(target)}
+                    {branch(sacrifice)}
+                </div>
+            }
+        </div>
+    }
+}
+
 #[derive(PartialEq, Properties)]
 struct ItemProps {
     item: Item,
@@ -299,10 +1448,16 @@ struct ItemProps {
     selected: bool,
     #[prop_or_default]
     hint: AttrValue,
+    #[prop_or(false)]
+    details: bool,
+    #[prop_or(false)]
+    pinned: bool,
 }
 
 #[function_component]
 fn ItemComponent(props: &ItemProps) -> Html {
+    let translations =
+        use_context::<Rc<Translations>>().expect("translations are provided by the app");
     let mut classes = vec![
         "item".to_string(),
         props
@@ -312,9 +1467,13 @@ fn ItemComponent(props: &ItemProps) -> Html {
             .to_lowercase()
             .replace(' ', "-"),
     ];
+    if let Some(material) = props.item.material() {
+        classes.push(format!("{material:?}").to_lowercase());
+    }
+
     let mut rarity = props.item.item_type().rarity();
 
-    if props.item.enchantments().len() > 0 {
+    if !props.item.enchantments().is_empty() {
         classes.push("enchanted".to_string());
         rarity.upgrade();
     }
@@ -325,13 +1484,16 @@ fn ItemComponent(props: &ItemProps) -> Html {
     if props.selected {
         classes.push("selected".to_string());
     }
+    if props.pinned {
+        classes.push("pinned".to_string());
+    }
 
     html! {
         <div class={classes!(classes)}>
             <span />
             <div>
                 <span class={rarity.class()}>
-                    {props.item.item_type()}
+                    {translations.item(&props.item)}
                 </span>
                 <div>
                     {for props.item.enchantments().iter().map(|(e, l)| {
@@ -343,13 +1505,30 @@ fn ItemComponent(props: &ItemProps) -> Html {
 
                         html! {
                             <span class={classes!(red)}>
-                                {e}{to_roman_numerals(*l)}
+                                {translations.enchantment_with_level(*e, *l)}
                             </span>
                         }
                     })}
                 </div>
 
-                if props.hint.as_str().len() > 0 {
+                if props.details {
+                    <div class="details">
+                        if let Some(durability) = props.item.max_durability() {
+                            <span>{translations.format("calculator.item.durability", &[&durability.to_string()])}</span>
+                        }
+                        if let Some(enchantability) = props.item.enchantability() {
+                            <span>{translations.format("calculator.item.enchantability", &[&enchantability.to_string()])}</span>
+                        }
+                        if let Some(repair_item) = translations.repair_item(&props.item) {
+                            <span>{translations.format("calculator.item.repaired_with", &[&repair_item])}</span>
+                        }
+                    </div>
+                }
+
+                if props.pinned {
+                    <div class="red">{translations.get("calculator.item.pinned")}</div>
+                }
+                if !props.hint.as_str().is_empty() {
                     <div class="blue">{props.hint.clone()}</div>
                 }
             </div>
@@ -365,6 +1544,8 @@ struct EnchantmentProps {
 
 #[function_component]
 fn EnchantmentComponent(props: &EnchantmentProps) -> Html {
+    let translations =
+        use_context::<Rc<Translations>>().expect("translations are provided by the app");
     let index = props.enchant as usize;
     let x = index % 8;
     let y = index / 8;
@@ -382,7 +1563,10 @@ fn EnchantmentComponent(props: &EnchantmentProps) -> Html {
                 <aside class="level">{level}</aside>
             }
             <div><span {class}>
-                {props.enchant}
+                {props.level.map_or_else(
+                    || translations.enchantment(props.enchant),
+                    |level| translations.enchantment_with_level(props.enchant, level),
+                )}
             </span></div>
         </div>
     }
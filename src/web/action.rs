@@ -1,47 +1,79 @@
-use yew::{classes, function_component, html, Html, Properties};
+use std::rc::Rc;
+
+use yew::{classes, function_component, html, use_context, Html, Properties};
+
+use crate::lang::Translations;
 
 #[derive(PartialEq, Properties)]
 pub struct ActionProps {
     pub action: Action,
+    /// a number shown in the corner of the slot, like an item count
+    #[prop_or_default]
+    pub level: Option<u32>,
+    #[prop_or(false)]
+    pub selected: bool,
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub enum Action {
     Remove,
+    Duplicate,
+    ClearEnchantments,
+    MaxEnchantments,
+    PriorWork,
+    PinTarget,
+    SplitBook,
 }
 
 impl Action {
     fn id(&self) -> &'static str {
         match self {
             Self::Remove => "remove",
+            Self::Duplicate => "duplicate",
+            Self::ClearEnchantments => "clear-enchantments",
+            Self::MaxEnchantments => "max-enchantments",
+            Self::PriorWork => "prior-work",
+            Self::PinTarget => "pin-target",
+            Self::SplitBook => "split-book",
         }
     }
 
     fn classes(&self) -> Vec<&'static str> {
         match self {
-            Self::Remove => vec!["red"],
+            Self::Remove | Self::ClearEnchantments => vec!["red"],
+            Self::MaxEnchantments => vec!["green"],
+            _ => vec![],
         }
     }
 
-    fn tooltip(&self) -> Option<&'static str> {
-        match self {
-            Self::Remove => None,
-        }
+    /// the translation key of the action's name. its tooltip, if it has one, is under `.tooltip`.
+    fn key(&self) -> String {
+        format!("calculator.action.{}", self.id().replace('-', "_"))
+    }
+
+    fn has_tooltip(&self) -> bool {
+        !matches!(self, Self::Remove)
     }
 }
 
 #[function_component]
 pub fn ActionComponent(props: &ActionProps) -> Html {
+    let translations =
+        use_context::<Rc<Translations>>().expect("translations are provided by the app");
+
     html! {
-        <div class={classes!("action", "hover", props.action.id())}>
+        <div class={classes!("action", "hover", props.action.id(), props.selected.then_some("selected"))}>
             <span />
+            if let Some(level) = props.level {
+                <aside class="level">{level}</aside>
+            }
             <div>
                 <span class={props.action.classes()}>
-                    {format!("{:?}", props.action)}
+                    {translations.get(&props.action.key())}
                 </span>
 
-                if let Some(tooltip) = props.action.tooltip() {
-                    <div class="blue">{tooltip}</div>
+                if props.action.has_tooltip() {
+                    <div class="blue">{translations.get(&format!("{}.tooltip", props.action.key()))}</div>
                 }
             </div>
         </div>
@@ -0,0 +1,148 @@
+use std::rc::Rc;
+
+use web_sys::HtmlInputElement;
+use yew::{
+    classes, function_component, html, use_context, Callback, DragEvent, Event, Html, MouseEvent,
+    Properties, TargetCast,
+};
+
+use crate::{
+    anvil::{Anvil, AnvilBehavior, MAX_COST},
+    item::Item,
+    lang::Translations,
+};
+
+use super::ItemComponent;
+
+/// the format inventory items are dragged in
+const DRAG_FORMAT: &str = "text/plain";
+
+#[derive(PartialEq, Properties)]
+pub struct AnvilGuiProps {
+    pub behavior: AnvilBehavior,
+    pub over_max: bool,
+    /// the items in the left and right input slots
+    pub slots: [Option<Item>; 2],
+    pub name: String,
+    /// called with the slot and the inventory index of an item dropped into it
+    pub ondrop: Callback<(usize, usize)>,
+    /// called with the slot that was clicked to empty it
+    pub onclear: Callback<usize>,
+    pub onrename: Callback<String>,
+}
+
+/// lets an inventory item be dragged into the anvil screen
+pub fn start_drag(ev: DragEvent, index: usize) {
+    if let Some(data) = ev.data_transfer() {
+        let _ = data.set_data(DRAG_FORMAT, &index.to_string());
+    }
+}
+
+/// the price and output of the anvil screen, like the game works it out
+fn output(props: &AnvilGuiProps) -> Option<(u32, Item)> {
+    let anvil = Anvil::new(props.behavior).with_over_max(props.over_max);
+    let renaming = !props.name.is_empty();
+
+    let (cost, item) = match &props.slots {
+        [Some(target), Some(sacrifice)] => {
+            let (cost, item, _) = anvil.combine(target.clone(), sacrifice.clone())?;
+            (cost + u32::from(renaming), item)
+        }
+        // an item on its own can only be renamed, which the game never makes too expensive
+        [Some(target), None] if renaming => {
+            let mut item = target.clone();
+            item.increment_anvil_uses();
+
+            ((target.work_penalty() + 1).min(MAX_COST), item)
+        }
+        _ => return None,
+    };
+
+    Some((cost, item))
+}
+
+#[function_component]
+pub fn AnvilGui(props: &AnvilGuiProps) -> Html {
+    let translations =
+        use_context::<Rc<Translations>>().expect("translations are provided by the app");
+    let slot = |index: usize| {
+        let ondrop = props.ondrop.clone();
+        let ondrop = Callback::from(move |ev: DragEvent| {
+            ev.prevent_default();
+
+            let dropped = ev
+                .data_transfer()
+                .and_then(|data| data.get_data(DRAG_FORMAT).ok())
+                .and_then(|data| data.parse().ok());
+            if let Some(dropped) = dropped {
+                ondrop.emit((index, dropped));
+            }
+        });
+        let onclick = props.onclear.reform(move |_: MouseEvent| index);
+
+        html! {
+            <div
+                class="anvil-slot"
+                ondragover={Callback::from(|ev: DragEvent| ev.prevent_default())}
+                {ondrop}
+                {onclick}
+            >
+                if let Some(item) = &props.slots[index] {
+                    <ItemComponent
+                        item={item.clone()}
+                        hint={translations.get("calculator.slot.take_out")}
+                    />
+                } else {
+                    <div class="empty" />
+                }
+            </div>
+        }
+    };
+
+    let output = output(props);
+    let onchange = props.onrename.reform(|ev: Event| {
+        let input: HtmlInputElement = ev.target_unchecked_into();
+        input.value()
+    });
+
+    html! {
+        <div class="anvil-gui">
+            <input
+                class="anvil-name"
+                placeholder={props.slots[0].as_ref().map(|item| translations.item(item))}
+                value={props.name.clone()}
+                maxlength="50"
+                {onchange}
+            />
+            <div class="anvil items">
+                {slot(0)}
+                <div />
+                {slot(1)}
+                <div />
+                <div class="anvil-slot">
+                    if let Some((_, item)) = &output {
+                        <ItemComponent
+                            item={item.clone()}
+                            hint={if props.name.is_empty() {
+                                String::new()
+                            } else {
+                                translations.format("calculator.rename", &[&props.name])
+                            }}
+                        />
+                    } else {
+                        <div class="empty" />
+                    }
+                </div>
+            </div>
+            if let Some((cost, _)) = output {
+                <span class={classes!(if cost > MAX_COST { "red" } else { "green-xp" })}>
+                    {if cost > MAX_COST {
+                        translations.get("container.repair.expensive")
+                    } else {
+                        translations.format("container.repair.cost", &[&cost.to_string()])
+                    }}
+                </span>
+            }
+        </div>
+    }
+}
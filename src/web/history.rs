@@ -0,0 +1,121 @@
+/// the most edits that are remembered
+const LIMIT: usize = 100;
+
+/// the undo and redo stacks of some state
+pub struct History<T> {
+    undo: Vec<T>,
+    redo: Vec<T>,
+}
+
+impl<T> Default for History<T> {
+    fn default() -> Self {
+        Self {
+            undo: Vec::new(),
+            redo: Vec::new(),
+        }
+    }
+}
+
+impl<T> History<T> {
+    /// records the state from before an edit. anything that was undone can't be redone anymore.
+    pub fn record(&mut self, state: T) {
+        if self.undo.len() == LIMIT {
+            self.undo.remove(0);
+        }
+
+        self.undo.push(state);
+        self.redo.clear();
+    }
+
+    /// returns the state from before the last edit, or `current` if there's nothing to undo
+    pub fn undo(&mut self, current: T) -> T {
+        match self.undo.pop() {
+            Some(previous) => {
+                self.redo.push(current);
+                previous
+            }
+            None => current,
+        }
+    }
+
+    /// returns the state from before the last undo, or `current` if there's nothing to redo
+    pub fn redo(&mut self, current: T) -> T {
+        match self.redo.pop() {
+            Some(next) => {
+                self.undo.push(current);
+                next
+            }
+            None => current,
+        }
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{History, LIMIT};
+
+    #[test]
+    fn undo_redo() {
+        let mut history = History::default();
+        assert!(!history.can_undo());
+        assert_eq!(history.undo(0), 0);
+
+        // edits go 0 -> 1 -> 2
+        history.record(0);
+        history.record(1);
+        assert!(history.can_undo());
+        assert!(!history.can_redo());
+
+        assert_eq!(history.undo(2), 1);
+        assert_eq!(history.undo(1), 0);
+        assert!(!history.can_undo());
+        assert_eq!(history.undo(0), 0);
+
+        assert_eq!(history.redo(0), 1);
+        assert_eq!(history.redo(1), 2);
+        assert!(!history.can_redo());
+        assert_eq!(history.redo(2), 2);
+    }
+
+    #[test]
+    fn new_edit_clears_redo() {
+        let mut history = History::default();
+        history.record(0);
+        history.record(1);
+        assert_eq!(history.undo(2), 1);
+        assert!(history.can_redo());
+
+        // editing from 1 to 3 forgets the undone 2
+        history.record(1);
+        assert!(!history.can_redo());
+        assert_eq!(history.redo(3), 3);
+        assert_eq!(history.undo(3), 1);
+        assert_eq!(history.undo(1), 0);
+    }
+
+    #[test]
+    fn history_limit() {
+        let mut history = History::default();
+        for state in 0..LIMIT + 10 {
+            history.record(state);
+        }
+
+        // only the latest edits are kept, so the oldest ones can't be undone
+        let mut current = LIMIT + 10;
+        let mut undone = 0;
+        while history.can_undo() {
+            current = history.undo(current);
+            undone += 1;
+        }
+        assert_eq!(undone, LIMIT);
+        assert_eq!(current, 10);
+    }
+}
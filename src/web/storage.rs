@@ -0,0 +1,113 @@
+//! saves the inventory and preferences in local storage, so they survive reloading the page.
+//!
+//! the inventory is stored with the permalink encoding, which is versioned on its own and can
+//! still read every format it has written. the storage layout itself is versioned by `VERSION_KEY`,
+//! and older layouts are migrated in `migrate` before anything is read.
+
+use web_sys::Storage;
+
+use crate::{
+    lang::Language,
+    permalink::{self, Setup},
+};
+
+/// the version of the storage layout written by `save`
+const VERSION: u32 = 1;
+
+const VERSION_KEY: &str = "anvil.version";
+/// the inventory and edition, in the permalink encoding
+const INVENTORY_KEY: &str = "anvil.inventory";
+/// the id of the language the site is shown in
+const LANGUAGE_KEY: &str = "anvil.language";
+
+fn storage() -> Option<Storage> {
+    web_sys::window()?.local_storage().ok().flatten()
+}
+
+/// how the stored layout compares to the one this version of the site writes
+#[derive(Debug, PartialEq)]
+enum StoredVersion {
+    /// nothing has been saved, or the version can't be read
+    Missing,
+    /// an older layout, which is migrated before it's read
+    Older(u32),
+    Current,
+    /// a newer version of the site wrote this, so it's left alone
+    Newer,
+}
+
+fn stored_version(stored: Option<&str>) -> StoredVersion {
+    match stored.and_then(|version| version.parse::<u32>().ok()) {
+        None => StoredVersion::Missing,
+        Some(version) if version < VERSION => StoredVersion::Older(version),
+        Some(VERSION) => StoredVersion::Current,
+        Some(_) => StoredVersion::Newer,
+    }
+}
+
+/// loads the saved edition and inventory. returns `None` if nothing was saved or it can't be read.
+pub fn load() -> Option<Setup> {
+    let storage = storage()?;
+
+    match stored_version(storage.get_item(VERSION_KEY).ok().flatten().as_deref()) {
+        StoredVersion::Missing | StoredVersion::Newer => return None,
+        StoredVersion::Older(version) => migrate(&storage, version),
+        StoredVersion::Current => {}
+    }
+
+    permalink::decode(&storage.get_item(INVENTORY_KEY).ok()??).ok()
+}
+
+/// saves the edition and inventory, replacing anything saved before.
+/// data from a newer version of the site is kept, so going back to an old page doesn't lose it.
+pub fn save(setup: &Setup) {
+    if let Some(storage) = storage() {
+        let stored = storage.get_item(VERSION_KEY).ok().flatten();
+        if stored_version(stored.as_deref()) == StoredVersion::Newer {
+            return;
+        }
+
+        let _ = storage.set_item(VERSION_KEY, &VERSION.to_string());
+        let _ = storage.set_item(INVENTORY_KEY, &permalink::encode(setup));
+    }
+}
+
+/// loads the language picked last time. returns `None` if none was picked.
+pub fn load_language() -> Option<Language> {
+    Language::from_id(&storage()?.get_item(LANGUAGE_KEY).ok()??)
+}
+
+pub fn save_language(language: Language) {
+    if let Some(storage) = storage() {
+        let _ = storage.set_item(LANGUAGE_KEY, language.id());
+    }
+}
+
+/// upgrades the storage layout from an older version, one version at a time
+fn migrate(storage: &Storage, version: u32) {
+    // there's only been one layout so far. when `VERSION` is bumped, add an arm here that moves
+    // the old keys over, e.g. `1 => { ... } 2 => { ... }`, falling through to the latest.
+    if version < VERSION {
+        let _ = storage.set_item(VERSION_KEY, &VERSION.to_string());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{stored_version, StoredVersion, VERSION};
+
+    #[test]
+    fn version_handling() {
+        assert_eq!(stored_version(None), StoredVersion::Missing);
+        assert_eq!(stored_version(Some("one")), StoredVersion::Missing);
+        assert_eq!(stored_version(Some("0")), StoredVersion::Older(0));
+        assert_eq!(
+            stored_version(Some(&VERSION.to_string())),
+            StoredVersion::Current
+        );
+        assert_eq!(
+            stored_version(Some(&(VERSION + 1).to_string())),
+            StoredVersion::Newer
+        );
+    }
+}